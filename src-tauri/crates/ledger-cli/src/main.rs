@@ -0,0 +1,405 @@
+//! `ledger`: query, search and add notes in a vault from a terminal or script, without
+//! the desktop app.
+//!
+//! ```text
+//! ledger query '#decision AND NOT #superseded' --vault ~/notes
+//! ledger search "rollback" --json
+//! ledger tags
+//! ledger new projects/x.md
+//! ```
+//!
+//! The vault is `--vault`, else `$LEDGER_VAULT`, else the nearest folder at or above the
+//! current directory that has a `.ledger/` folder (one the app has opened). `query`,
+//! `search` and `tags` do not write to the vault. Exit codes are listed in `USAGE`.
+
+use ledger_core::index::LEDGER_DIR;
+use ledger_core::{LedgerError, QueryResultItem, SearchMatch, SearchOptions, TagInfo, Vault};
+use serde::Serialize;
+use std::path::Path;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: ledger <command> [options]
+
+Commands:
+  query <expr>    List items matching a query, e.g. '#decision AND path:projects/*'
+                  --inherited       #tag terms also match tags on parent items
+                  --sort-by <key>   Order by an attribute (key:: value)
+                  --desc            Sort descending
+  search <text>   Full-text search
+                  --fuzzy           Fuzzy match, best lines first
+                  --case-sensitive  --whole-word  --regex
+  tags            List tags with usage counts
+  new <path>      Create an empty note
+
+Options:
+  --vault <dir>   Vault folder (default: $LEDGER_VAULT, or the nearest folder up
+                  from here with a .ledger/ folder)
+  --json          Print JSON instead of text (errors too, on stderr)
+  -h, --help      Show this help
+
+Exit codes:
+  0  Success
+  1  query/search found nothing
+  2  Invalid arguments, path or query syntax
+  3  Vault not found or could not be opened
+  4  Command failed (e.g. the note already exists)
+";
+
+const EXIT_NO_MATCHES: u8 = 1;
+const EXIT_USAGE: u8 = 2;
+const EXIT_VAULT: u8 = 3;
+const EXIT_FAILED: u8 = 4;
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Query {
+        expr: String,
+        inherited: bool,
+        sort_by: Option<String>,
+        descending: bool,
+    },
+    Search {
+        text: String,
+        fuzzy: bool,
+        options: SearchOptions,
+    },
+    Tags,
+    New {
+        path: String,
+    },
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+struct Args {
+    command: Command,
+    vault: Option<String>,
+    json: bool,
+}
+
+/// Parse arguments (without the program name). Options may come before or after the
+/// command's positional argument.
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut positional = Vec::new();
+    let mut vault = None;
+    let mut json = false;
+    let mut inherited = false;
+    let mut sort_by = None;
+    let mut descending = false;
+    let mut fuzzy = false;
+    let mut options = SearchOptions::default();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("{} needs a value", name))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => {
+                return Ok(Args {
+                    command: Command::Help,
+                    vault,
+                    json,
+                })
+            }
+            "--vault" => vault = Some(value("--vault")?),
+            "--json" => json = true,
+            "--inherited" => inherited = true,
+            "--sort-by" => sort_by = Some(value("--sort-by")?),
+            "--desc" => descending = true,
+            "--fuzzy" => fuzzy = true,
+            "--case-sensitive" => options.case_sensitive = true,
+            "--whole-word" => options.whole_word = true,
+            "--regex" => options.regex = true,
+            "--" => positional.extend(iter.by_ref().cloned()),
+            a if a.starts_with("--") => return Err(format!("Unknown option: {}", a)),
+            a => positional.push(a.to_string()),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    let name = positional.next().ok_or("No command given")?;
+    let mut operand = |what: &str| {
+        positional
+            .next()
+            .ok_or_else(|| format!("{} needs {}", name, what))
+    };
+    let command = match name.as_str() {
+        "query" => Command::Query {
+            expr: operand("a query")?,
+            inherited,
+            sort_by,
+            descending,
+        },
+        "search" => Command::Search {
+            text: operand("search text")?,
+            fuzzy,
+            options,
+        },
+        "tags" => Command::Tags,
+        "new" => Command::New {
+            path: operand("a path")?,
+        },
+        "help" => Command::Help,
+        other => return Err(format!("Unknown command: {}", other)),
+    };
+    if let Some(extra) = positional.next() {
+        return Err(format!("Unexpected argument: {}", extra));
+    }
+    Ok(Args {
+        command,
+        vault,
+        json,
+    })
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(s) => println!("{}", s),
+        Err(e) => eprintln!("ledger: {}", e),
+    }
+}
+
+fn print_query(results: &[QueryResultItem]) {
+    for r in results {
+        let line = r.node.span.start.line + 1;
+        println!("{}:{}: {}", r.file_path, line, r.node.text);
+    }
+}
+
+fn print_search(results: &[SearchMatch]) {
+    for m in results {
+        println!("{}:{}: {}", m.file_path, m.line + 1, m.snippet_or_line);
+    }
+}
+
+fn print_tags(tags: &[TagInfo]) {
+    let width = tags.iter().map(|t| t.name.len()).max().unwrap_or(0);
+    for t in tags {
+        let files = if t.file_count == 1 { "file" } else { "files" };
+        println!(
+            "#{:<width$}  {} ({} {})",
+            t.name,
+            t.node_count,
+            t.file_count,
+            files,
+            width = width
+        );
+    }
+}
+
+/// Exit code for a query or search: success only if something was found.
+fn found(count: usize) -> ExitCode {
+    if count == 0 {
+        ExitCode::from(EXIT_NO_MATCHES)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn fail(code: u8, message: &str) -> ExitCode {
+    eprintln!("ledger: {}", message);
+    ExitCode::from(code)
+}
+
+/// Report a failed command. Bad input is a usage error, anything else a failure.
+fn fail_with(e: &LedgerError, json: bool) -> ExitCode {
+    let code = match e {
+        LedgerError::Parse { .. }
+        | LedgerError::InvalidInput { .. }
+        | LedgerError::InvalidPath { .. }
+        | LedgerError::OutsideVault { .. } => EXIT_USAGE,
+        _ => EXIT_FAILED,
+    };
+    fail_json(code, e, json)
+}
+
+fn fail_json(code: u8, e: &LedgerError, json: bool) -> ExitCode {
+    if json {
+        match serde_json::to_string(e) {
+            Ok(s) => eprintln!("{}", s),
+            Err(_) => eprintln!("ledger: {}", e),
+        }
+        ExitCode::from(code)
+    } else {
+        fail(code, &e.to_string())
+    }
+}
+
+/// The nearest folder at or above `dir` with a `.ledger/` folder.
+fn find_vault(dir: &Path) -> Option<String> {
+    dir.ancestors()
+        .find(|d| d.join(LEDGER_DIR).is_dir())
+        .map(|d| d.to_string_lossy().into_owned())
+}
+
+fn run(args: Args) -> ExitCode {
+    if args.command == Command::Help {
+        print!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+    let path = args
+        .vault
+        .or_else(|| std::env::var("LEDGER_VAULT").ok())
+        .or_else(|| find_vault(&std::env::current_dir().ok()?));
+    let Some(path) = path else {
+        if args.json {
+            return fail_json(EXIT_VAULT, &LedgerError::NoVault, true);
+        }
+        return fail(
+            EXIT_VAULT,
+            "No vault here: pass --vault, set LEDGER_VAULT, or run inside a vault",
+        );
+    };
+    // Only `new` changes the vault; the others leave no index behind.
+    let open = match args.command {
+        Command::New { .. } => Vault::open,
+        _ => Vault::open_read_only,
+    };
+    let mut vault = match open(&path) {
+        Ok(v) => v,
+        Err(e) => return fail_json(EXIT_VAULT, &e, args.json),
+    };
+
+    match args.command {
+        Command::Query {
+            expr,
+            inherited,
+            sort_by,
+            descending,
+        } => match vault.query(&expr, None, inherited, sort_by.as_deref(), descending) {
+            Ok(results) => {
+                if args.json {
+                    print_json(&results);
+                } else {
+                    print_query(&results);
+                }
+                found(results.len())
+            }
+            Err(e) => fail_with(&e, args.json),
+        },
+        Command::Search {
+            text,
+            fuzzy,
+            options,
+        } => match vault.search(&text, None, fuzzy, options) {
+            Ok(results) => {
+                if args.json {
+                    print_json(&results);
+                } else {
+                    print_search(&results);
+                }
+                found(results.len())
+            }
+            Err(e) => fail_with(&e, args.json),
+        },
+        Command::Tags => {
+            let tags = vault.list_tags();
+            if args.json {
+                print_json(&tags);
+            } else {
+                print_tags(&tags);
+            }
+            ExitCode::SUCCESS
+        }
+        Command::New { path } => {
+            if !path.ends_with(".md") {
+                let e = LedgerError::InvalidPath {
+                    path,
+                    reason: "note path must end in .md".into(),
+                };
+                return fail_json(EXIT_USAGE, &e, args.json);
+            }
+            match vault.create_file(&path) {
+                Ok(()) => {
+                    if args.json {
+                        print_json(&serde_json::json!({ "path": path }));
+                    } else {
+                        println!("Created {}", path);
+                    }
+                    ExitCode::SUCCESS
+                }
+                Err(e) => fail_with(&e, args.json),
+            }
+        }
+        Command::Help => unreachable!("handled above"),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match parse_args(&args) {
+        Ok(args) => run(args),
+        // `--json` may be among the arguments that did not parse.
+        Err(e) if args.iter().any(|a| a == "--json") => {
+            fail_json(EXIT_USAGE, &LedgerError::InvalidInput { message: e }, true)
+        }
+        Err(e) => {
+            eprintln!("ledger: {}\n\n{}", e, USAGE);
+            ExitCode::from(EXIT_USAGE)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &[&str]) -> Result<Args, String> {
+        parse_args(&s.iter().map(|a| a.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn options_before_or_after_the_command() {
+        let a = args(&[
+            "--vault",
+            "/v",
+            "query",
+            "#decision",
+            "--json",
+            "--sort-by",
+            "due",
+        ])
+        .unwrap();
+        assert_eq!(a.vault.as_deref(), Some("/v"));
+        assert!(a.json);
+        assert_eq!(
+            a.command,
+            Command::Query {
+                expr: "#decision".into(),
+                inherited: false,
+                sort_by: Some("due".into()),
+                descending: false,
+            }
+        );
+
+        let a = args(&["search", "--regex", "--", "--flag"]).unwrap();
+        match a.command {
+            Command::Search { text, options, .. } => {
+                assert_eq!(text, "--flag");
+                assert!(options.regex);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn usage_errors() {
+        assert!(args(&[]).unwrap_err().contains("No command"));
+        assert!(args(&["query"]).unwrap_err().contains("needs a query"));
+        assert!(args(&["tags", "extra"]).unwrap_err().contains("Unexpected"));
+        assert!(args(&["tags", "--vault"])
+            .unwrap_err()
+            .contains("needs a value"));
+        assert!(args(&["tags", "--bogus"])
+            .unwrap_err()
+            .contains("Unknown option"));
+        assert!(args(&["frobnicate"])
+            .unwrap_err()
+            .contains("Unknown command"));
+        assert_eq!(args(&["query", "--help"]).unwrap().command, Command::Help);
+    }
+}
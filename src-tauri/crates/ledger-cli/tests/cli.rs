@@ -0,0 +1,103 @@
+//! Integration test: run the `ledger` binary against a temp vault.
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn ledger(vault: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ledger"))
+        .args(args)
+        .arg("--vault")
+        .arg(vault)
+        .output()
+        .unwrap()
+}
+
+/// Run without `--vault`, from `dir`.
+fn ledger_in(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ledger"))
+        .args(args)
+        .current_dir(dir)
+        .env_remove("LEDGER_VAULT")
+        .output()
+        .unwrap()
+}
+
+fn stdout(out: &Output) -> String {
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+#[test]
+fn query_search_tags_and_new() {
+    let temp = std::env::temp_dir().join("ledger_test_cli");
+    let _ = fs::remove_dir_all(&temp);
+    fs::create_dir_all(&temp).unwrap();
+    fs::write(
+        temp.join("adr.md"),
+        "- Use Postgres #decision\n- Rollback plan #ops\n",
+    )
+    .unwrap();
+
+    let out = ledger(&temp, &["query", "#decision"]);
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(stdout(&out), "adr.md:1: Use Postgres #decision\n");
+
+    let out = ledger(&temp, &["search", "rollback", "--json"]);
+    assert_eq!(out.status.code(), Some(0));
+    let json: serde_json::Value = serde_json::from_str(&stdout(&out)).unwrap();
+    assert_eq!(json[0]["file_path"], "adr.md");
+    assert_eq!(json[0]["line"], 1);
+
+    // Nothing found, bad query syntax, unknown command.
+    assert_eq!(ledger(&temp, &["search", "kafka"]).status.code(), Some(1));
+    assert_eq!(ledger(&temp, &["query", "#a AND"]).status.code(), Some(2));
+    assert_eq!(ledger(&temp, &["frobnicate"]).status.code(), Some(2));
+
+    let out = ledger(&temp, &["tags"]);
+    assert_eq!(
+        stdout(&out),
+        "#decision  1 (1 file)\n#ops       1 (1 file)\n"
+    );
+    // Reading leaves nothing behind in the vault.
+    assert!(!temp.join(".ledger").exists());
+
+    let out = ledger(&temp, &["new", "projects/x.md"]);
+    assert_eq!(out.status.code(), Some(0));
+    assert!(temp.join("projects/x.md").is_file());
+    assert_eq!(
+        ledger(&temp, &["new", "projects/x.md"]).status.code(),
+        Some(4)
+    );
+
+    let missing = temp.join("no-such-vault");
+    assert_eq!(ledger(&missing, &["tags"]).status.code(), Some(3));
+
+    // With --json, errors are objects with a stable code.
+    let out = ledger(&temp, &["new", "projects/x.md", "--json"]);
+    let err: serde_json::Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["code"], "AlreadyExists");
+    assert_eq!(err["path"], "projects/x.md");
+    let out = ledger(&temp, &["new", "x.txt", "--json"]);
+    assert_eq!(out.status.code(), Some(2));
+    let err: serde_json::Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["code"], "InvalidPath");
+    let out = ledger(&temp, &["frobnicate", "--json"]);
+    assert_eq!(out.status.code(), Some(2));
+    let err: serde_json::Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["code"], "InvalidInput");
+
+    // Without --vault, the vault is found from a folder inside it (`new` created
+    // `.ledger/`), and outside any vault nothing is opened.
+    let out = ledger_in(&temp.join("projects"), &["query", "#ops"]);
+    assert_eq!(stdout(&out), "adr.md:2: Rollback plan #ops\n");
+    let elsewhere = std::env::temp_dir().join("ledger_test_cli_no_vault");
+    fs::create_dir_all(&elsewhere).unwrap();
+    let out = ledger_in(&elsewhere, &["tags", "--json"]);
+    assert_eq!(out.status.code(), Some(3));
+    let err: serde_json::Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(err["code"], "NoVault");
+    assert!(!elsewhere.join(".ledger").exists());
+    let _ = fs::remove_dir_all(&elsewhere);
+
+    let _ = fs::remove_dir_all(&temp);
+}
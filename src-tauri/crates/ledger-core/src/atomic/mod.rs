@@ -0,0 +1,185 @@
+//! Crash-safe file writes. The new content goes to a temp file in the same folder,
+//! is flushed to disk, and then renamed over the target, so a crash or full disk
+//! leaves either the old file or the new one, never a truncated mix.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Why a write failed, for errors the user can act on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteFailure {
+    DiskFull,
+    ReadOnly,
+    Other,
+}
+
+/// Classify an I/O error from a write. Uses raw OS codes because the matching
+/// `ErrorKind` variants need a newer Rust than the crate's minimum.
+pub fn classify(e: &io::Error) -> WriteFailure {
+    // ENOSPC is 28 everywhere; EDQUOT differs, and each number means something else
+    // on the other platforms.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    const DISK_FULL: &[i32] = &[28, 122]; // ENOSPC, EDQUOT
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "dragonfly"
+    ))]
+    const DISK_FULL: &[i32] = &[28, 69]; // ENOSPC, EDQUOT
+    #[cfg(all(
+        unix,
+        not(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "dragonfly"
+        ))
+    ))]
+    const DISK_FULL: &[i32] = &[28]; // ENOSPC
+    #[cfg(unix)]
+    const READ_ONLY: &[i32] = &[30]; // EROFS
+    #[cfg(windows)]
+    const DISK_FULL: &[i32] = &[39, 112]; // ERROR_HANDLE_DISK_FULL, ERROR_DISK_FULL
+    #[cfg(windows)]
+    const READ_ONLY: &[i32] = &[19]; // ERROR_WRITE_PROTECT
+    #[cfg(not(any(unix, windows)))]
+    const DISK_FULL: &[i32] = &[];
+    #[cfg(not(any(unix, windows)))]
+    const READ_ONLY: &[i32] = &[];
+
+    match e.raw_os_error() {
+        Some(code) if DISK_FULL.contains(&code) => WriteFailure::DiskFull,
+        Some(code) if READ_ONLY.contains(&code) => WriteFailure::ReadOnly,
+        _ => WriteFailure::Other,
+    }
+}
+
+/// Hidden temp file next to `path`, unique within this process.
+fn temp_path(path: &Path) -> PathBuf {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(".{}.{}-{}.tmp", name, std::process::id(), n))
+}
+
+fn write_temp(tmp: &Path, contents: &[u8], perms: Option<fs::Permissions>) -> io::Result<()> {
+    let mut file = File::options().write(true).create_new(true).open(tmp)?;
+    file.write_all(contents)?;
+    if let Some(perms) = perms {
+        file.set_permissions(perms)?;
+    }
+    file.sync_all()
+}
+
+/// Replace `path` with `contents` atomically. An existing file keeps its permissions.
+/// The parent folder must exist.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let perms = fs::metadata(path).ok().map(|m| m.permissions());
+    let tmp = temp_path(path);
+    let result = write_temp(&tmp, contents, perms).and_then(|()| fs::rename(&tmp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+        return result;
+    }
+    // Persist the rename itself. Not supported for directories on every platform.
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        if let Ok(d) = File::open(dir) {
+            let _ = d.sync_all();
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_content_without_leaving_temp_files() {
+        let dir = std::env::temp_dir().join("ledger_test_atomic");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("note.md");
+
+        write_atomic(&path, b"- first").unwrap();
+        write_atomic(&path, b"- second").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "- second");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join("ledger_test_atomic_perms");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("note.md");
+        fs::write(&path, "- old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+
+        write_atomic(&path, b"- new").unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn classifies_disk_full_and_read_only() {
+        assert_eq!(
+            classify(&io::Error::from_raw_os_error(28)),
+            WriteFailure::DiskFull
+        );
+        assert_eq!(
+            classify(&io::Error::from_raw_os_error(30)),
+            WriteFailure::ReadOnly
+        );
+        assert_eq!(
+            classify(&io::Error::from_raw_os_error(2)),
+            WriteFailure::Other
+        );
+        // EDQUOT on this platform; the other platform's number is something else.
+        #[cfg(target_os = "linux")]
+        let (quota, other) = (122, 69); // 69 is ESRMNT
+        #[cfg(target_os = "macos")]
+        let (quota, other) = (69, 122);
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        {
+            assert_eq!(
+                classify(&io::Error::from_raw_os_error(quota)),
+                WriteFailure::DiskFull
+            );
+            assert_eq!(
+                classify(&io::Error::from_raw_os_error(other)),
+                WriteFailure::Other
+            );
+        }
+    }
+
+    #[test]
+    fn failed_write_leaves_target_untouched() {
+        let dir = std::env::temp_dir().join("ledger_test_atomic_fail");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("target.md")).unwrap();
+        // Renaming a file over a directory fails after the temp file was written.
+        assert!(write_atomic(&dir.join("target.md"), b"x").is_err());
+        assert!(dir.join("target.md").is_dir());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
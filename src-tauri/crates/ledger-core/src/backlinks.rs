@@ -0,0 +1,148 @@
+//! "What links here": list items whose `[[wikilinks]]` resolve to a note, or to one
+//! item in it by its `^block-id`.
+
+use crate::error::LedgerError;
+use crate::markdown::{self, links};
+use crate::query::{effective_tags, result_item, QueryResultItem};
+use crate::vault_path::VaultPath;
+
+use super::vault::Vault;
+
+impl Vault {
+    /// Every list item in the vault with a wikilink to the note at `path`, in file order,
+    /// including links from the note itself. With `node_id` (a `stable_id` or `id`),
+    /// only links to that item's block id count; an item without a `^block-id` has no
+    /// backlinks.
+    pub fn backlinks(
+        &mut self,
+        path: &str,
+        node_id: Option<&str>,
+    ) -> Result<Vec<QueryResultItem>, LedgerError> {
+        let target = VaultPath::new(self.root(), path)?;
+        let target = target.as_str();
+        if !self.file_paths.iter().any(|p| p == target) {
+            return Err(LedgerError::NotFound {
+                path: target.to_string(),
+            });
+        }
+        let file_list = self.file_paths.clone();
+        self.refresh_index(&file_list);
+
+        let block_id = match node_id {
+            Some(id) => {
+                let nodes = self.index.get(target).map_or(&[][..], |e| &e.nodes[..]);
+                let node =
+                    markdown::find_node(nodes, id).ok_or_else(|| LedgerError::NodeNotFound {
+                        path: target.to_string(),
+                        node_id: id.to_string(),
+                    })?;
+                match &node.block_id {
+                    Some(b) => Some(b.clone()),
+                    None => return Ok(Vec::new()),
+                }
+            }
+            None => None,
+        };
+
+        let mut results = Vec::new();
+        for rel_path in &file_list {
+            let nodes = match self.index.get(rel_path) {
+                Some(entry) => &entry.nodes,
+                None => continue,
+            };
+            for node in nodes {
+                let links_here = node.links.iter().any(|link| {
+                    (block_id.is_none() || link.block_id == block_id)
+                        && links::resolve_wikilink(&link.target, rel_path, &file_list)
+                            == Some(target)
+                });
+                if links_here {
+                    results.push(result_item(
+                        rel_path,
+                        nodes,
+                        node,
+                        effective_tags(nodes, node),
+                    ));
+                }
+            }
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn links_to_a_note_and_to_an_item() {
+        let root = std::env::temp_dir().join("ledger_test_backlinks");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("projects")).unwrap();
+        fs::write(
+            root.join("projects/db.md"),
+            "- Use Postgres #decision ^pg\n- Schema draft\n- See [[#^pg]]",
+        )
+        .unwrap();
+        fs::write(
+            root.join("log.md"),
+            "- Week 1\n  - Chose db per [[db^pg]]\n  - Read [[projects/db]] again\n- [[plan]]",
+        )
+        .unwrap();
+
+        let mut vault = Vault::open(root.to_str().unwrap()).unwrap();
+        let db = std::path::Path::new("projects").join("db.md");
+        let db = db.to_str().unwrap();
+
+        let texts = |items: &[QueryResultItem]| -> Vec<String> {
+            items.iter().map(|i| i.node.text.clone()).collect()
+        };
+        let all = vault.backlinks(db, None).unwrap();
+        assert_eq!(
+            texts(&all),
+            vec![
+                "Chose db per [[db^pg]]",
+                "Read [[projects/db]] again",
+                "See [[#^pg]]"
+            ]
+        );
+        assert_eq!(all[0].file_path, "log.md");
+        assert_eq!(all[0].parent_path.as_deref(), Some("Week 1"));
+
+        let to_item = vault.backlinks(db, Some("pg")).unwrap();
+        assert_eq!(
+            texts(&to_item),
+            vec!["Chose db per [[db^pg]]", "See [[#^pg]]"]
+        );
+        // Without a block id nothing can link to the item.
+        let schema = vault.index.get(db).unwrap().nodes[1].stable_id.clone();
+        assert!(vault.backlinks(db, Some(&schema)).unwrap().is_empty());
+
+        assert_eq!(
+            vault.backlinks(db, Some("nope")).unwrap_err().code(),
+            "NodeNotFound"
+        );
+        assert_eq!(
+            vault.backlinks("plan.md", None).unwrap_err().code(),
+            "NotFound"
+        );
+
+        // Moving the note rewrites the links to it, so the backlinks survive.
+        let moved = std::path::Path::new("archive").join("database.md");
+        let moved = moved.to_str().unwrap();
+        let result = vault.move_file(db, moved, true).unwrap();
+        assert_eq!(result.links_updated, vec!["log.md"]);
+        assert_eq!(
+            texts(&vault.backlinks(moved, None).unwrap()),
+            vec![
+                "See [[#^pg]]",
+                "Chose db per [[database^pg]]",
+                "Read [[archive/database]] again"
+            ]
+        );
+        assert_eq!(vault.backlinks(moved, Some("pg")).unwrap().len(), 2);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
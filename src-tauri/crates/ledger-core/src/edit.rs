@@ -0,0 +1,83 @@
+//! Edits to individual list items, written back to the file on disk.
+
+use crate::error::LedgerError;
+use crate::markdown;
+
+use super::file::{read_file_impl, write_file_impl};
+use super::vault::Vault;
+
+/// Core block-id logic for testing. Returns the node's block id, writing a new
+/// `^block-id` marker into the file if the node does not have one yet.
+pub fn assign_block_id_impl(
+    root_path: &str,
+    path: &str,
+    node_id: &str,
+) -> Result<String, LedgerError> {
+    let content = read_file_impl(root_path, path)?;
+    let nodes = markdown::parse_list_items(&content);
+    let node = find_node(&nodes, path, node_id)?;
+    if let Some(existing) = &node.block_id {
+        return Ok(existing.clone());
+    }
+    let block_id = markdown::new_block_id(&nodes, node);
+    let updated = markdown::insert_block_id(&content, node, &block_id);
+    write_file_impl(root_path, path, &updated)?;
+    Ok(block_id)
+}
+
+/// Core checkbox toggle for testing. Open becomes done; done and cancelled become open.
+/// Returns the new state.
+pub fn toggle_task_impl(
+    root_path: &str,
+    path: &str,
+    node_id: &str,
+) -> Result<markdown::TaskState, LedgerError> {
+    let content = read_file_impl(root_path, path)?;
+    let nodes = markdown::parse_list_items(&content);
+    let node = find_node(&nodes, path, node_id)?;
+    let next = match node.task {
+        Some(markdown::TaskState::Open) => markdown::TaskState::Done,
+        Some(_) => markdown::TaskState::Open,
+        None => return Err(not_a_task(node_id)),
+    };
+    let updated =
+        markdown::set_task_state(&content, node, next).ok_or_else(|| not_a_task(node_id))?;
+    write_file_impl(root_path, path, &updated)?;
+    Ok(next)
+}
+
+fn find_node<'a>(
+    nodes: &'a [markdown::TreeNode],
+    path: &str,
+    node_id: &str,
+) -> Result<&'a markdown::TreeNode, LedgerError> {
+    markdown::find_node(nodes, node_id).ok_or_else(|| LedgerError::NodeNotFound {
+        path: path.to_string(),
+        node_id: node_id.to_string(),
+    })
+}
+
+fn not_a_task(node_id: &str) -> LedgerError {
+    LedgerError::invalid_input(format!("Node is not a task: {}", node_id))
+}
+
+impl Vault {
+    /// Block id of a list item, adding one to the file if needed (see
+    /// `assign_block_id_impl`).
+    pub fn assign_block_id(&mut self, path: &str, node_id: &str) -> Result<String, LedgerError> {
+        let block_id = assign_block_id_impl(&self.root_path, path, node_id)?;
+        self.reindex_file(path);
+        Ok(block_id)
+    }
+
+    /// Toggle a task item's checkbox. Returns the new state.
+    pub fn toggle_task(
+        &mut self,
+        path: &str,
+        node_id: &str,
+    ) -> Result<markdown::TaskState, LedgerError> {
+        let next = toggle_task_impl(&self.root_path, path, node_id)?;
+        self.reindex_file(path);
+        Ok(next)
+    }
+}
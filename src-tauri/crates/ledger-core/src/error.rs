@@ -0,0 +1,258 @@
+//! `LedgerError`: the error type of every vault operation.
+//!
+//! Errors cross the Tauri boundary as JSON objects with a stable `code` (the variant
+//! name), a human-readable `message`, and the variant's fields, e.g.
+//!
+//! ```json
+//! { "code": "AlreadyExists", "message": "Already exists: notes/a.md", "path": "notes/a.md" }
+//! ```
+//!
+//! The frontend branches on `code`; `message` is only for display and may change.
+
+use crate::atomic::{self, WriteFailure};
+use crate::query_lang::ParseError;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::io;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LedgerError {
+    /// A command needs an open vault and none is open.
+    NoVault,
+    /// The file or folder does not exist.
+    NotFound { path: String },
+    /// The path (or the symlink it goes through) points outside the vault.
+    OutsideVault { path: String },
+    /// The path is not acceptable for this operation, e.g. it contains `..`, names the
+    /// app's `.ledger/` folder, or is a folder where a note was expected.
+    InvalidPath { path: String, reason: String },
+    /// The file or folder to be created is already there.
+    AlreadyExists { path: String },
+    /// The file changed on disk since the version the caller read. `version` and
+    /// `content` are the current ones, or None if the file was deleted.
+    Conflict {
+        path: String,
+        version: Option<String>,
+        content: Option<String>,
+    },
+    /// No list item with this id in the file.
+    NodeNotFound { path: String, node_id: String },
+    /// No trash entry with this id.
+    TrashEntryNotFound { id: String },
+    /// A write failed because the disk is full.
+    DiskFull { path: String },
+    /// A write failed because the file or filesystem is read-only.
+    ReadOnly { path: String },
+    /// A query, regex or stored JSON file could not be parsed. `offset` is the byte
+    /// offset of the problem in the input, when known.
+    Parse {
+        message: String,
+        offset: Option<usize>,
+    },
+    /// An argument is invalid for reasons other than its path (empty query, bad tag
+    /// name, conflicting options).
+    InvalidInput { message: String },
+    /// Any other filesystem error.
+    Io { message: String },
+}
+
+impl LedgerError {
+    /// Stable error code; the variant name.
+    pub fn code(&self) -> &'static str {
+        match self {
+            LedgerError::NoVault => "NoVault",
+            LedgerError::NotFound { .. } => "NotFound",
+            LedgerError::OutsideVault { .. } => "OutsideVault",
+            LedgerError::InvalidPath { .. } => "InvalidPath",
+            LedgerError::AlreadyExists { .. } => "AlreadyExists",
+            LedgerError::Conflict { .. } => "Conflict",
+            LedgerError::NodeNotFound { .. } => "NodeNotFound",
+            LedgerError::TrashEntryNotFound { .. } => "TrashEntryNotFound",
+            LedgerError::DiskFull { .. } => "DiskFull",
+            LedgerError::ReadOnly { .. } => "ReadOnly",
+            LedgerError::Parse { .. } => "Parse",
+            LedgerError::InvalidInput { .. } => "InvalidInput",
+            LedgerError::Io { .. } => "Io",
+        }
+    }
+
+    pub(crate) fn invalid_path(path: &str, reason: impl Into<String>) -> Self {
+        LedgerError::InvalidPath {
+            path: path.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    pub(crate) fn invalid_input(message: impl Into<String>) -> Self {
+        LedgerError::InvalidInput {
+            message: message.into(),
+        }
+    }
+
+    /// Classify an I/O error on `path`: missing files, existing files, full disks and
+    /// read-only filesystems get their own codes, anything else is `Io`.
+    pub fn io(path: &str, e: io::Error) -> Self {
+        let path = path.to_string();
+        match e.kind() {
+            io::ErrorKind::NotFound => return LedgerError::NotFound { path },
+            io::ErrorKind::AlreadyExists => return LedgerError::AlreadyExists { path },
+            _ => {}
+        }
+        match atomic::classify(&e) {
+            WriteFailure::DiskFull => LedgerError::DiskFull { path },
+            WriteFailure::ReadOnly => LedgerError::ReadOnly { path },
+            WriteFailure::Other => LedgerError::Io {
+                message: format!("{}: {}", path, e),
+            },
+        }
+    }
+}
+
+impl fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LedgerError::NoVault => write!(f, "No vault open"),
+            LedgerError::NotFound { path } => write!(f, "Not found: {}", path),
+            LedgerError::OutsideVault { path } => write!(f, "Path is outside vault: {}", path),
+            LedgerError::InvalidPath { path, reason } => {
+                write!(f, "Invalid path {:?}: {}", path, reason)
+            }
+            LedgerError::AlreadyExists { path } => write!(f, "Already exists: {}", path),
+            LedgerError::Conflict { path, version, .. } => match version {
+                Some(_) => write!(f, "{} changed on disk since it was read", path),
+                None => write!(f, "{} was deleted on disk since it was read", path),
+            },
+            LedgerError::NodeNotFound { path, node_id } => {
+                write!(f, "Node not found: {} in {}", node_id, path)
+            }
+            LedgerError::TrashEntryNotFound { id } => write!(f, "Trash entry not found: {}", id),
+            LedgerError::DiskFull { path } => write!(f, "Disk full: could not save {}", path),
+            LedgerError::ReadOnly { path } => write!(f, "Read-only: could not save {}", path),
+            LedgerError::Parse {
+                message,
+                offset: Some(offset),
+            } => write!(f, "{} (at position {})", message, offset + 1),
+            LedgerError::Parse {
+                message,
+                offset: None,
+            } => write!(f, "{}", message),
+            LedgerError::InvalidInput { message } | LedgerError::Io { message } => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LedgerError {}
+
+impl Serialize for LedgerError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("code", self.code())?;
+        map.serialize_entry("message", &self.to_string())?;
+        match self {
+            LedgerError::NoVault => {}
+            LedgerError::NotFound { path }
+            | LedgerError::OutsideVault { path }
+            | LedgerError::AlreadyExists { path }
+            | LedgerError::DiskFull { path }
+            | LedgerError::ReadOnly { path } => map.serialize_entry("path", path)?,
+            LedgerError::InvalidPath { path, reason } => {
+                map.serialize_entry("path", path)?;
+                map.serialize_entry("reason", reason)?;
+            }
+            LedgerError::Conflict {
+                path,
+                version,
+                content,
+            } => {
+                map.serialize_entry("path", path)?;
+                map.serialize_entry("version", version)?;
+                map.serialize_entry("content", content)?;
+            }
+            LedgerError::NodeNotFound { path, node_id } => {
+                map.serialize_entry("path", path)?;
+                map.serialize_entry("node_id", node_id)?;
+            }
+            LedgerError::TrashEntryNotFound { id } => map.serialize_entry("id", id)?,
+            LedgerError::Parse { offset, .. } => map.serialize_entry("offset", offset)?,
+            LedgerError::InvalidInput { .. } | LedgerError::Io { .. } => {}
+        }
+        map.end()
+    }
+}
+
+impl From<ParseError> for LedgerError {
+    fn from(e: ParseError) -> Self {
+        LedgerError::Parse {
+            message: e.message,
+            offset: Some(e.offset),
+        }
+    }
+}
+
+impl From<io::Error> for LedgerError {
+    /// For I/O errors without a path to report; prefer `LedgerError::io`.
+    fn from(e: io::Error) -> Self {
+        LedgerError::Io {
+            message: e.to_string(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for LedgerError {
+    fn from(e: serde_json::Error) -> Self {
+        if e.is_io() {
+            LedgerError::Io {
+                message: e.to_string(),
+            }
+        } else {
+            LedgerError::Parse {
+                message: e.to_string(),
+                offset: None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_code_message_and_details() {
+        let e = LedgerError::Conflict {
+            path: "a.md".into(),
+            version: None,
+            content: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&e).unwrap(),
+            serde_json::json!({
+                "code": "Conflict",
+                "message": "a.md was deleted on disk since it was read",
+                "path": "a.md",
+                "version": null,
+                "content": null,
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(LedgerError::NoVault).unwrap(),
+            serde_json::json!({ "code": "NoVault", "message": "No vault open" })
+        );
+    }
+
+    #[test]
+    fn io_errors_are_classified() {
+        let missing = io::Error::from(io::ErrorKind::NotFound);
+        assert_eq!(
+            LedgerError::io("a.md", missing),
+            LedgerError::NotFound {
+                path: "a.md".into()
+            }
+        );
+        let other = io::Error::other("boom");
+        assert_eq!(LedgerError::io("a.md", other).code(), "Io");
+    }
+}
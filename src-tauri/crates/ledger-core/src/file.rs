@@ -0,0 +1,496 @@
+use crate::atomic;
+use crate::error::LedgerError;
+use crate::index::LEDGER_DIR;
+use crate::markdown::{self, links};
+use crate::vault_path::VaultPath;
+use serde::Serialize;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use super::trash::{move_to_trash, TrashEntry};
+use super::vault::Vault;
+
+fn read(path: &VaultPath) -> Result<String, LedgerError> {
+    std::fs::read_to_string(path.full()).map_err(|e| LedgerError::io(path.as_str(), e))
+}
+
+/// Core read logic for testing without Tauri state.
+pub fn read_file_impl(root_path: &str, path: &str) -> Result<String, LedgerError> {
+    read(&VaultPath::new(Path::new(root_path), path)?)
+}
+
+/// File content with the version it was read at.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileContent {
+    pub content: String,
+    pub version: String,
+}
+
+/// Version token: modification time (ms) and content hash, as `"<mtime>-<hash>"`.
+/// Only the hash decides whether a file changed, so a sync tool that merely touches
+/// the file does not cause a conflict.
+fn file_version(full: &Path, content: &str) -> String {
+    let mtime = std::fs::metadata(full)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_millis());
+    format!("{}-{}", mtime, markdown::content_hash(content))
+}
+
+fn version_hash(version: &str) -> &str {
+    version.rsplit('-').next().unwrap_or(version)
+}
+
+/// True if two version tokens name the same content (see `file_version`).
+pub(crate) fn same_version(a: &str, b: &str) -> bool {
+    version_hash(a) == version_hash(b)
+}
+
+fn read_versioned(path: &VaultPath) -> Result<FileContent, LedgerError> {
+    let content = read(path)?;
+    Ok(FileContent {
+        version: file_version(path.full(), &content),
+        content,
+    })
+}
+
+/// Core versioned read for testing.
+pub fn read_file_versioned_impl(root_path: &str, path: &str) -> Result<FileContent, LedgerError> {
+    read_versioned(&VaultPath::new(Path::new(root_path), path)?)
+}
+
+/// Write `content` atomically. A symlink (to somewhere in the vault) is written through,
+/// not replaced, since `full()` is its target.
+fn write(path: &VaultPath, content: &str) -> Result<(), LedgerError> {
+    path.create_parents()?;
+    atomic::write_atomic(path.full(), content.as_bytes())
+        .map_err(|e| LedgerError::io(path.as_str(), e))
+}
+
+/// Core write logic for testing.
+pub fn write_file_impl(root_path: &str, path: &str, content: &str) -> Result<(), LedgerError> {
+    write(&VaultPath::for_create(Path::new(root_path), path)?, content)
+}
+
+/// Core checked write for testing. With `expected_version` set, fails with
+/// `LedgerError::Conflict` if the file on disk no longer matches it. Returns the new
+/// version.
+pub fn write_file_checked_impl(
+    root_path: &str,
+    path: &str,
+    content: &str,
+    expected_version: Option<&str>,
+) -> Result<String, LedgerError> {
+    let path = VaultPath::for_create(Path::new(root_path), path)?;
+    if let Some(expected) = expected_version {
+        match read_versioned(&path) {
+            Ok(current) if same_version(&current.version, expected) => {}
+            Ok(current) => {
+                return Err(LedgerError::Conflict {
+                    path: path.to_string(),
+                    version: Some(current.version),
+                    content: Some(current.content),
+                })
+            }
+            Err(LedgerError::NotFound { .. }) => {
+                return Err(LedgerError::Conflict {
+                    path: path.to_string(),
+                    version: None,
+                    content: None,
+                })
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    write(&path, content)?;
+    Ok(file_version(path.full(), content))
+}
+
+/// Core create logic for testing.
+pub fn create_file_impl(root_path: &str, path: &str) -> Result<(), LedgerError> {
+    let path = VaultPath::for_create(Path::new(root_path), path)?;
+    if path.exists() {
+        return Err(LedgerError::AlreadyExists {
+            path: path.to_string(),
+        });
+    }
+    path.create_parents()?;
+    // `create_new` fails rather than follow a link created since the check.
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path.full())
+        .map_err(|e| LedgerError::io(path.as_str(), e))?;
+    Ok(())
+}
+
+/// Core delete logic for testing. The file is moved to the vault trash, not removed.
+/// If `path` is a symlink, the link is deleted and its target is left alone.
+pub fn delete_file_impl(root_path: &str, path: &str) -> Result<TrashEntry, LedgerError> {
+    let path = VaultPath::new(Path::new(root_path), path)?;
+    check_is_file(&path)?;
+    move_to_trash(&path)
+}
+
+fn check_is_file(path: &VaultPath) -> Result<(), LedgerError> {
+    if !path.exists() {
+        return Err(LedgerError::NotFound {
+            path: path.to_string(),
+        });
+    }
+    if !path.full().is_file() {
+        return Err(LedgerError::invalid_path(path.as_str(), "not a file"));
+    }
+    Ok(())
+}
+
+fn check_is_folder(path: &VaultPath) -> Result<(), LedgerError> {
+    if !path.exists() {
+        return Err(LedgerError::NotFound {
+            path: path.to_string(),
+        });
+    }
+    if !path.full().is_dir() {
+        return Err(LedgerError::invalid_path(path.as_str(), "not a folder"));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+pub struct MoveResult {
+    pub from: String,
+    pub to: String,
+    /// Files whose links to the old path were rewritten.
+    pub links_updated: Vec<String>,
+    /// Files whose links could not be rewritten. The move itself still happened.
+    pub link_errors: Vec<LinkUpdateError>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LinkUpdateError {
+    pub file_path: String,
+    pub error: LedgerError,
+}
+
+/// Core move/rename logic for testing. Moves `from` to `to` (both vault-relative),
+/// creating destination folders as needed. Refuses to overwrite an existing file. A
+/// symlink is moved itself, not the note it points at. With `update_links`, markdown
+/// links and `[[wikilinks]]` in `vault_paths` that point at the old path are rewritten
+/// to the new one; a file that cannot be written is reported in `link_errors` and does
+/// not fail the move.
+pub fn move_file_impl(
+    root_path: &str,
+    from: &str,
+    to: &str,
+    update_links: bool,
+    vault_paths: &[String],
+) -> Result<MoveResult, LedgerError> {
+    let root = Path::new(root_path);
+    let src = VaultPath::new(root, from)?;
+    check_is_file(&src)?;
+    if !to.ends_with(".md") {
+        return Err(LedgerError::invalid_path(
+            to,
+            "destination must be a .md file",
+        ));
+    }
+    let dest = VaultPath::for_create(root, to)?;
+    // A case-only rename on a case-insensitive filesystem resolves to the same file.
+    // Compared with `entry()` so a symlink is never moved over the note it points at.
+    let same_file = dest.full().canonicalize().is_ok_and(|d| d == src.entry());
+    if dest.exists() && !same_file {
+        return Err(LedgerError::AlreadyExists {
+            path: dest.to_string(),
+        });
+    }
+    dest.create_parents()?;
+    std::fs::rename(src.entry(), dest.full()).map_err(|e| LedgerError::io(src.as_str(), e))?;
+
+    let (from, to) = (src.as_str(), dest.as_str());
+    let mut links_updated = Vec::new();
+    let mut link_errors = Vec::new();
+    if update_links {
+        for old in vault_paths {
+            let path = if old == from { to } else { old.as_str() };
+            let Ok(content) = read_file_impl(root_path, path) else {
+                continue;
+            };
+            let markdown = links::retarget_links(&content, path, from, to);
+            let text = markdown.as_deref().unwrap_or(&content);
+            let updated = links::retarget_wikilinks(text, old, from, to, vault_paths).or(markdown);
+            if let Some(updated) = updated {
+                match write_file_impl(root_path, path, &updated) {
+                    Ok(()) => links_updated.push(path.to_string()),
+                    Err(error) => link_errors.push(LinkUpdateError {
+                        file_path: path.to_string(),
+                        error,
+                    }),
+                }
+            }
+        }
+    }
+    Ok(MoveResult {
+        from: from.to_string(),
+        to: to.to_string(),
+        links_updated,
+        link_errors,
+    })
+}
+
+/// Core folder-create logic for testing. Creates missing parent folders too.
+pub fn create_folder_impl(root_path: &str, path: &str) -> Result<(), LedgerError> {
+    let path = VaultPath::for_create(Path::new(root_path), path)?;
+    if path.exists() {
+        return Err(LedgerError::AlreadyExists {
+            path: path.to_string(),
+        });
+    }
+    path.create_parents()?;
+    std::fs::create_dir(path.full()).map_err(|e| LedgerError::io(path.as_str(), e))
+}
+
+/// Vault paths of `file_paths` under `folder`, paired with where they end up when the
+/// folder becomes `new_folder`.
+fn paths_under(file_paths: &[String], folder: &str, new_folder: &str) -> Vec<(String, String)> {
+    file_paths
+        .iter()
+        .filter_map(|p| {
+            let rest = Path::new(p).strip_prefix(folder).ok()?;
+            let moved = Path::new(new_folder).join(rest);
+            Some((p.clone(), moved.to_string_lossy().into_owned()))
+        })
+        .collect()
+}
+
+/// Core folder rename/move logic for testing. Refuses to overwrite an existing path or
+/// move a folder into itself. A symlinked folder is moved as a link. Returns (old, new) paths of the notes that moved.
+pub fn rename_folder_impl(
+    root_path: &str,
+    from: &str,
+    to: &str,
+    vault_paths: &[String],
+) -> Result<Vec<(String, String)>, LedgerError> {
+    let root = Path::new(root_path);
+    let src = VaultPath::new(root, from)?;
+    check_is_folder(&src)?;
+    let dest = VaultPath::for_create(root, to)?;
+    if dest.exists() {
+        return Err(LedgerError::AlreadyExists {
+            path: dest.to_string(),
+        });
+    }
+    if dest.full().starts_with(src.entry()) {
+        return Err(LedgerError::invalid_path(
+            to,
+            "cannot move a folder into itself",
+        ));
+    }
+    dest.create_parents()?;
+    std::fs::rename(src.entry(), dest.full()).map_err(|e| LedgerError::io(src.as_str(), e))?;
+    Ok(paths_under(vault_paths, src.as_str(), dest.as_str()))
+}
+
+/// Core folder-delete logic for testing. Moves the folder and everything in it to the
+/// vault trash. Returns the vault paths of the notes that were removed.
+pub fn delete_folder_impl(
+    root_path: &str,
+    path: &str,
+    vault_paths: &[String],
+) -> Result<Vec<String>, LedgerError> {
+    let path = VaultPath::new(Path::new(root_path), path)?;
+    check_is_folder(&path)?;
+    move_to_trash(&path)?;
+    Ok(paths_under(vault_paths, path.as_str(), path.as_str())
+        .into_iter()
+        .map(|(old, _)| old)
+        .collect())
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    Folder,
+    File,
+}
+
+/// One folder or note in `list_tree`.
+#[derive(Debug, Serialize)]
+pub struct TreeEntry {
+    pub name: String,
+    /// Vault-relative path.
+    pub path: String,
+    pub kind: EntryKind,
+    /// File size in bytes; None for folders.
+    pub size: Option<u64>,
+    /// Last modification time (Unix ms), if the platform reports it.
+    pub modified_ms: Option<u64>,
+    /// Folder contents, folders first, then by name. Empty for files.
+    pub children: Vec<TreeEntry>,
+}
+
+fn tree_entries(dir: &Path, root: &Path) -> std::io::Result<Vec<TreeEntry>> {
+    let mut out = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        // `file_type` does not follow symlinks. Linked folders are skipped: they could
+        // lead outside the vault or back into it (`loop -> .`).
+        let file_type = entry.file_type()?;
+        let is_dir = file_type.is_dir();
+        if (is_dir && name == LEDGER_DIR) || (file_type.is_symlink() && path.is_dir()) {
+            continue;
+        }
+        if !is_dir && path.extension().map_or(true, |e| e != "md") {
+            continue;
+        }
+        // A linked note reports its target's size; a broken link is left out.
+        let Ok(meta) = std::fs::metadata(&path) else {
+            continue;
+        };
+        let modified_ms = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_millis() as u64);
+        let rel = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .into_owned();
+        out.push(TreeEntry {
+            name,
+            path: rel,
+            kind: if is_dir {
+                EntryKind::Folder
+            } else {
+                EntryKind::File
+            },
+            size: (!is_dir).then_some(meta.len()),
+            modified_ms,
+            children: if is_dir {
+                tree_entries(&path, root)?
+            } else {
+                Vec::new()
+            },
+        });
+    }
+    out.sort_by(|a, b| {
+        let a_file = matches!(a.kind, EntryKind::File);
+        let b_file = matches!(b.kind, EntryKind::File);
+        a_file.cmp(&b_file).then_with(|| a.name.cmp(&b.name))
+    });
+    Ok(out)
+}
+
+/// Core tree listing for testing: every folder (including empty ones) and .md file
+/// under the vault root, nested. `.ledger/` and symlinked folders are skipped.
+pub fn list_tree_impl(root_path: &str) -> Result<Vec<TreeEntry>, LedgerError> {
+    let root = Path::new(root_path)
+        .canonicalize()
+        .map_err(|e| LedgerError::io(root_path, e))?;
+    tree_entries(&root, &root).map_err(|e| LedgerError::io(root_path, e))
+}
+
+impl Vault {
+    pub fn read_file(&self, path: &str) -> Result<FileContent, LedgerError> {
+        read_file_versioned_impl(&self.root_path, path)
+    }
+
+    /// Write a note (see `write_file_checked_impl`) and re-index it. Returns the new
+    /// version.
+    pub fn write_file(
+        &mut self,
+        path: &str,
+        content: &str,
+        expected_version: Option<&str>,
+    ) -> Result<String, LedgerError> {
+        let path = VaultPath::for_create(self.root(), path)?;
+        let version =
+            write_file_checked_impl(&self.root_path, path.as_str(), content, expected_version)?;
+        self.reindex_file(path.as_str());
+        Ok(version)
+    }
+
+    /// Create an empty note and add it to the vault.
+    pub fn create_file(&mut self, path: &str) -> Result<(), LedgerError> {
+        let path = VaultPath::for_create(self.root(), path)?;
+        create_file_impl(&self.root_path, path.as_str())?;
+        self.add_file(path.as_str());
+        Ok(())
+    }
+
+    /// Move a note to the vault trash. If `path` is a symlink, only the link is deleted.
+    pub fn delete_file(&mut self, path: &str) -> Result<TrashEntry, LedgerError> {
+        let entry = delete_file_impl(&self.root_path, path)?;
+        self.remove_file(&entry.original_path);
+        Ok(entry)
+    }
+
+    /// Move a note (see `move_file_impl`), optionally rewriting links to it.
+    pub fn move_file(
+        &mut self,
+        from: &str,
+        to: &str,
+        update_links: bool,
+    ) -> Result<MoveResult, LedgerError> {
+        let result = move_file_impl(&self.root_path, from, to, update_links, &self.file_paths)?;
+        self.remove_file(&result.from);
+        self.add_file(&result.to);
+        for p in &result.links_updated {
+            self.reindex_file(p);
+        }
+        Ok(result)
+    }
+
+    /// Rename a note within its folder. `new_name` is a file name, not a path.
+    pub fn rename_file(
+        &mut self,
+        path: &str,
+        new_name: &str,
+        update_links: bool,
+    ) -> Result<MoveResult, LedgerError> {
+        if new_name.is_empty() || new_name.contains(['/', '\\']) {
+            return Err(LedgerError::invalid_path(
+                new_name,
+                "new name must be a file name, not a path",
+            ));
+        }
+        let to = match Path::new(path).parent() {
+            Some(dir) => dir.join(new_name).to_string_lossy().into_owned(),
+            None => new_name.to_string(),
+        };
+        self.move_file(path, &to, update_links)
+    }
+
+    pub fn create_folder(&self, path: &str) -> Result<(), LedgerError> {
+        create_folder_impl(&self.root_path, path)
+    }
+
+    /// Rename or move a folder. Returns the new paths of the notes that moved.
+    pub fn rename_folder(&mut self, from: &str, to: &str) -> Result<Vec<String>, LedgerError> {
+        let moved = rename_folder_impl(&self.root_path, from, to, &self.file_paths)?;
+        for (old, new) in &moved {
+            self.forget_file(old);
+            self.file_paths.retain(|p| p != old);
+            self.file_paths.push(new.clone());
+            self.reindex_file(new);
+        }
+        self.file_paths.sort();
+        Ok(moved.into_iter().map(|(_, new)| new).collect())
+    }
+
+    /// Move a folder to the vault trash. Returns the paths of the notes that were removed.
+    pub fn delete_folder(&mut self, path: &str) -> Result<Vec<String>, LedgerError> {
+        let removed = delete_folder_impl(&self.root_path, path, &self.file_paths)?;
+        for p in &removed {
+            self.remove_file(p);
+        }
+        Ok(removed)
+    }
+
+    pub fn list_tree(&self) -> Result<Vec<TreeEntry>, LedgerError> {
+        list_tree_impl(&self.root_path)
+    }
+}
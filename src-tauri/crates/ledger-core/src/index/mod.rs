@@ -0,0 +1,260 @@
+//! Persistent vault index: parsed list items per file, stored in
+//! `<vault>/.ledger/index.json`. Entries are keyed by relative path and reused while the
+//! file's mtime and size are unchanged, so queries skip re-reading and re-parsing
+//! untouched files. File text is kept in memory for search but not stored, so the index
+//! stays a fraction of the vault's size.
+//!
+//! The index is a cache: if it is missing, corrupt, from another version, or older than
+//! the files, the affected entries are rebuilt from the files. That is why saving after
+//! single-file updates is batched (`save_if_due`) instead of rewriting the whole index
+//! on every write.
+
+use crate::atomic;
+use crate::markdown::{self, TreeNode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+/// Vault-local directory for app data (index, trash). Skipped when listing notes.
+pub const LEDGER_DIR: &str = ".ledger";
+const INDEX_FILENAME: &str = "index.json";
+/// Bump when `FileEntry` or `TreeNode` change shape so old indexes are rebuilt.
+const INDEX_VERSION: u32 = 6;
+/// Minimum time between two saves by `save_if_due`.
+const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileEntry {
+    pub mtime_ns: u64,
+    pub size: u64,
+    /// The file's text. Not stored in `index.json`; None after loading until
+    /// `load_contents` reads the file.
+    #[serde(skip)]
+    pub content: Option<String>,
+    pub nodes: Vec<TreeNode>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct VaultIndex {
+    version: u32,
+    files: BTreeMap<String, FileEntry>,
+    #[serde(skip)]
+    dirty: bool,
+    #[serde(skip)]
+    last_save: Option<Instant>,
+}
+
+fn index_path(root: &Path) -> PathBuf {
+    root.join(LEDGER_DIR).join(INDEX_FILENAME)
+}
+
+/// (mtime in ns, size) for a file, or None if it cannot be read.
+fn file_stamp(path: &Path) -> Option<(u64, u64)> {
+    let meta = std::fs::metadata(path).ok()?;
+    if !meta.is_file() {
+        return None;
+    }
+    let mtime = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some((mtime.as_nanos() as u64, meta.len()))
+}
+
+impl VaultIndex {
+    /// Load the index for `root`; an empty index if none is usable.
+    pub fn load(root: &Path) -> VaultIndex {
+        let loaded = std::fs::read_to_string(index_path(root))
+            .ok()
+            .and_then(|s| serde_json::from_str::<VaultIndex>(&s).ok());
+        match loaded {
+            Some(index) if index.version == INDEX_VERSION => index,
+            _ => VaultIndex {
+                version: INDEX_VERSION,
+                files: BTreeMap::new(),
+                dirty: true,
+                last_save: None,
+            },
+        }
+    }
+
+    /// Write the index if it changed since the last load or save.
+    pub fn save(&mut self, root: &Path) -> Result<(), String> {
+        if !self.dirty {
+            return Ok(());
+        }
+        let path = index_path(root);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        atomic::write_atomic(&path, json.as_bytes()).map_err(|e| e.to_string())?;
+        self.dirty = false;
+        self.last_save = Some(Instant::now());
+        Ok(())
+    }
+
+    /// `save`, unless the index was saved less than `SAVE_INTERVAL` ago. Changes left
+    /// unsaved are written by the next due save or `save`.
+    pub fn save_if_due(&mut self, root: &Path) -> Result<(), String> {
+        match self.last_save {
+            Some(at) if at.elapsed() < SAVE_INTERVAL => Ok(()),
+            _ => self.save(root),
+        }
+    }
+
+    /// Whether there are changes that have not been saved yet.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Re-read and re-parse any of `paths` whose mtime or size changed. Unreadable
+    /// files are dropped from the index. Returns true if any entry was updated.
+    pub fn refresh(&mut self, root: &Path, paths: &[String]) -> bool {
+        let mut changed = false;
+        for rel in paths {
+            let stamp = file_stamp(&root.join(rel));
+            let fresh = match (stamp, self.files.get(rel)) {
+                (Some((mtime, size)), Some(e)) => e.mtime_ns == mtime && e.size == size,
+                _ => false,
+            };
+            if !fresh {
+                self.update_file(root, rel);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Index (or re-index) one file after it was written.
+    pub fn update_file(&mut self, root: &Path, rel: &str) {
+        let full = root.join(rel);
+        let entry = file_stamp(&full).and_then(|(mtime_ns, size)| {
+            let content = std::fs::read_to_string(&full).ok()?;
+            Some(FileEntry {
+                mtime_ns,
+                size,
+                nodes: markdown::parse_list_items(&content),
+                content: Some(content),
+            })
+        });
+        match entry {
+            Some(e) => {
+                self.files.insert(rel.to_string(), e);
+                self.dirty = true;
+            }
+            None => self.remove_file(rel),
+        }
+    }
+
+    pub fn remove_file(&mut self, rel: &str) {
+        if self.files.remove(rel).is_some() {
+            self.dirty = true;
+        }
+    }
+
+    /// Drop entries for files that are no longer in the vault.
+    pub fn retain_paths(&mut self, paths: &[String]) {
+        let before = self.files.len();
+        self.files.retain(|k, _| paths.contains(k));
+        if self.files.len() != before {
+            self.dirty = true;
+        }
+    }
+
+    pub fn get(&self, rel: &str) -> Option<&FileEntry> {
+        self.files.get(rel)
+    }
+
+    /// Read the text of entries in `paths` loaded without it. Call after `refresh`, so
+    /// the files match their entries.
+    pub fn load_contents(&mut self, root: &Path, paths: &[String]) {
+        for rel in paths {
+            if let Some(entry) = self.files.get_mut(rel) {
+                if entry.content.is_none() {
+                    entry.content = std::fs::read_to_string(root.join(rel)).ok();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn refresh_reparses_changed_files_and_persists() {
+        let root = std::env::temp_dir().join("ledger_test_index");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("a.md"), "- One #decision").unwrap();
+
+        let paths = vec!["a.md".to_string(), "missing.md".to_string()];
+        let mut index = VaultIndex::load(&root);
+        index.refresh(&root, &paths);
+        assert_eq!(index.get("a.md").unwrap().nodes[0].tags, vec!["decision"]);
+        assert!(index.get("missing.md").is_none());
+        index.save(&root).unwrap();
+        let json = fs::read_to_string(root.join(".ledger/index.json")).unwrap();
+        assert!(!json.contains("\"content\""));
+
+        // A reloaded index is reused as-is until the file changes; text is read back
+        // on demand.
+        let mut index = VaultIndex::load(&root);
+        assert!(!index.dirty);
+        index.refresh(&root, &paths);
+        assert!(!index.dirty);
+        assert_eq!(index.get("a.md").unwrap().content, None);
+        index.load_contents(&root, &paths);
+        assert_eq!(
+            index.get("a.md").unwrap().content.as_deref(),
+            Some("- One #decision")
+        );
+
+        fs::write(root.join("a.md"), "- One #decision\n- Two #ci").unwrap();
+        index.refresh(&root, &paths);
+        assert_eq!(index.get("a.md").unwrap().nodes.len(), 2);
+
+        index.retain_paths(&[]);
+        assert!(index.get("a.md").is_none());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn corrupt_index_is_rebuilt() {
+        let root = std::env::temp_dir().join("ledger_test_index_corrupt");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join(LEDGER_DIR)).unwrap();
+        fs::write(root.join(".ledger/index.json"), "{not json").unwrap();
+        fs::write(root.join("a.md"), "- x").unwrap();
+
+        let mut index = VaultIndex::load(&root);
+        index.refresh(&root, &["a.md".to_string()]);
+        assert_eq!(index.get("a.md").unwrap().content.as_deref(), Some("- x"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn save_if_due_batches_saves() {
+        let root = std::env::temp_dir().join("ledger_test_index_batched");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("a.md"), "- x").unwrap();
+
+        let mut index = VaultIndex::load(&root);
+        index.update_file(&root, "a.md");
+        index.save_if_due(&root).unwrap();
+        assert!(!index.is_dirty());
+
+        fs::write(root.join("a.md"), "- y").unwrap();
+        index.update_file(&root, "a.md");
+        index.save_if_due(&root).unwrap();
+        assert!(index.is_dirty(), "saved again right after the last save");
+        index.save(&root).unwrap();
+        assert!(!index.is_dirty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,49 @@
+//! Developer Ledger without the app: open a folder of markdown notes as a [`Vault`],
+//! read and write notes, and query, search and edit their list items.
+//!
+//! The desktop app wraps these APIs in Tauri commands; scripts and tests can use them
+//! directly.
+//!
+//! ```no_run
+//! let mut vault = ledger_core::Vault::open("/path/to/notes")?;
+//! for item in vault.query("#decision AND NOT #superseded", None, false, None, false)? {
+//!     println!("{}: {}", item.file_path, item.node.text);
+//! }
+//! # Ok::<(), ledger_core::LedgerError>(())
+//! ```
+
+pub mod atomic;
+pub mod backlinks;
+pub mod edit;
+pub mod error;
+pub mod file;
+pub mod index;
+pub mod markdown;
+pub mod query;
+pub mod query_lang;
+pub mod replace;
+pub mod search;
+pub mod tags;
+pub mod trash;
+pub mod vault;
+pub mod vault_path;
+
+pub use edit::{assign_block_id_impl, toggle_task_impl};
+pub use error::LedgerError;
+pub use file::{
+    create_file_impl, create_folder_impl, delete_file_impl, delete_folder_impl, list_tree_impl,
+    move_file_impl, read_file_impl, read_file_versioned_impl, rename_folder_impl,
+    write_file_checked_impl, write_file_impl, EntryKind, FileContent, LinkUpdateError, MoveResult,
+    TreeEntry,
+};
+pub use markdown::links::WikiLink;
+pub use markdown::{TaskState, TreeNode};
+pub use query::QueryResultItem;
+pub use replace::{replace_in_vault_impl, FileReplace, ReplaceSelection};
+pub use search::{SearchMatch, SearchOptions};
+pub use tags::{rename_tag_impl, TagInfo, TagRenameReport};
+pub use trash::{
+    list_trash_impl, purge_trash_impl, restore_from_trash_impl, RestoreConflict, TrashEntry,
+};
+pub use vault::{open_vault_impl, OpenVaultResult, Vault};
+pub use vault_path::VaultPath;
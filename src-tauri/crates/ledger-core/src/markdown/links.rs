@@ -0,0 +1,419 @@
+//! Links between notes.
+//!
+//! - Markdown links `[text](other.md)`, with targets relative to the linking file. Used
+//!   to keep links working when a file is moved.
+//! - Wikilinks in list item text: `[[note]]`, `[[note#heading]]`, `[[note^block-id]]`
+//!   (Obsidian's `[[note#^block-id]]` too) and `[[note|shown text]]`. The note is a
+//!   name or vault-relative path, with or without `.md`; `[[#heading]]` and
+//!   `[[^block-id]]` point into the linking file itself.
+//!
+//! Both kinds are rewritten when the note they point at is moved.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::sync::OnceLock;
+
+/// A `[[wikilink]]` as written in item text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WikiLink {
+    /// Note name or path before any `#` or `^`; empty for links into the same file.
+    pub target: String,
+    /// Heading after `#`, if the link points at one.
+    pub heading: Option<String>,
+    /// Block id after `^` (or `#^`), without the `^`.
+    pub block_id: Option<String>,
+    /// Text after `|`, shown instead of the target.
+    pub alias: Option<String>,
+}
+
+fn wikilink_regex() -> &'static Regex {
+    static_regex!(r"\[\[([^\[\]|\n]+)(?:\|([^\[\]\n]*))?\]\]")
+}
+
+/// Byte ranges of the `[[...]]` wikilinks in `text`, brackets included.
+pub fn wikilink_ranges(text: &str) -> Vec<Range<usize>> {
+    wikilink_regex()
+        .find_iter(text)
+        .map(|m| m.range())
+        .collect()
+}
+
+/// Parse every `[[...]]` wikilink in `text`, in order. Links with nothing to point at
+/// (`[[ ]]`, `[[#]]`) are skipped.
+pub fn extract_wikilinks(text: &str) -> Vec<WikiLink> {
+    wikilink_regex()
+        .captures_iter(text)
+        .filter_map(|c| {
+            let inner = c.get(1).unwrap().as_str();
+            let (target, rest) = match inner.find(['#', '^']) {
+                Some(i) => inner.split_at(i),
+                None => (inner, ""),
+            };
+            let non_empty = |s: &str| Some(s.trim().to_string()).filter(|s| !s.is_empty());
+            let (heading, block_id) = match rest.strip_prefix("#^").or(rest.strip_prefix('^')) {
+                Some(block) => (None, non_empty(block)),
+                None => (rest.strip_prefix('#').and_then(non_empty), None),
+            };
+            let target = target.trim().to_string();
+            if target.is_empty() && heading.is_none() && block_id.is_none() {
+                return None;
+            }
+            Some(WikiLink {
+                target,
+                heading,
+                block_id,
+                alias: c.get(2).and_then(|a| non_empty(a.as_str())),
+            })
+        })
+        .collect()
+}
+
+/// `path` with `/` separators, without a trailing `.md`, lower-cased: how wikilink
+/// targets are compared.
+fn link_key(path: &str) -> String {
+    let path = path.replace('\\', "/").to_lowercase();
+    match path.strip_suffix(".md") {
+        Some(stem) => stem.to_string(),
+        None => path,
+    }
+}
+
+/// The note in `file_paths` that wikilink `target`, written in `from_path`, points at.
+/// An empty target is `from_path` itself. Otherwise, ignoring case and a `.md` suffix,
+/// the first of: the vault-relative path `target`; `target` relative to the linking
+/// file's folder; the note whose path ends in `/target` with the fewest folders (then
+/// alphabetically first). None if no note matches.
+pub fn resolve_wikilink<'a>(
+    target: &str,
+    from_path: &str,
+    file_paths: &'a [String],
+) -> Option<&'a str> {
+    let find = |key: &str| {
+        file_paths
+            .iter()
+            .find(|p| link_key(p) == key)
+            .map(String::as_str)
+    };
+    let from_key = link_key(from_path);
+    if target.is_empty() {
+        return find(&from_key);
+    }
+    let key = link_key(target.trim_start_matches('/'));
+    if let Some(p) = normalize(&key).and_then(|k| find(&k)) {
+        return Some(p);
+    }
+    if let Some(p) = normalize(&format!("{}/{}", dir_of(&from_key), key)).and_then(|k| find(&k)) {
+        return Some(p);
+    }
+    let suffix = format!("/{}", key.trim_start_matches("./"));
+    file_paths
+        .iter()
+        .filter(|p| link_key(p).ends_with(&suffix))
+        .min_by_key(|p| (link_key(p).matches('/').count(), p.as_str()))
+        .map(String::as_str)
+}
+
+/// Vault-relative path with `/` separators, `.` and `..` resolved. None if it climbs
+/// above the vault root.
+fn normalize(path: &str) -> Option<String> {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split(['/', '\\']) {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            p => parts.push(p),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+/// Folder part of a vault-relative path ("" for files at the root).
+fn dir_of(path: &str) -> &str {
+    path.rfind('/').map_or("", |i| &path[..i])
+}
+
+/// Path from folder `from_dir` to `target` (both vault-relative, `/` separated).
+fn relative_path(from_dir: &str, target: &str) -> String {
+    let from: Vec<&str> = from_dir.split('/').filter(|p| !p.is_empty()).collect();
+    let to: Vec<&str> = target.split('/').collect();
+    let common = from
+        .iter()
+        .zip(&to)
+        .take_while(|(a, b)| a == b)
+        .count()
+        .min(to.len() - 1);
+    let mut parts: Vec<&str> = vec![".."; from.len() - common];
+    parts.extend(&to[common..]);
+    parts.join("/")
+}
+
+/// Rewrite links in `content` (the text of `file_path`) that point at `old_path` so
+/// they point at `new_path`. Paths are vault-relative. External URLs and links to other
+/// files are untouched; `#fragment` suffixes are kept. Returns None if nothing changed.
+pub fn retarget_links(
+    content: &str,
+    file_path: &str,
+    old_path: &str,
+    new_path: &str,
+) -> Option<String> {
+    let re = static_regex!(r"\]\(([^)\s]+)\)");
+    let file_path = normalize(file_path)?;
+    let old_path = normalize(old_path)?;
+    let new_path = normalize(new_path)?;
+    let dir = dir_of(&file_path);
+
+    let mut out = String::with_capacity(content.len());
+    let mut copied = 0;
+    for c in re.captures_iter(content) {
+        let target = c.get(1).unwrap();
+        let (path, fragment) = match target.as_str().find('#') {
+            Some(i) => target.as_str().split_at(i),
+            None => (target.as_str(), ""),
+        };
+        if path.is_empty() || path.contains("://") || path.starts_with("mailto:") {
+            continue;
+        }
+        let resolved = match path.strip_prefix('/') {
+            Some(abs) => normalize(abs),
+            None => normalize(&format!("{}/{}", dir, path)),
+        };
+        if resolved.as_deref() != Some(old_path.as_str()) {
+            continue;
+        }
+        let new_target = if path.starts_with('/') {
+            format!("/{}", new_path)
+        } else {
+            relative_path(dir, &new_path)
+        };
+        out.push_str(&content[copied..target.start()]);
+        out.push_str(&new_target);
+        out.push_str(fragment);
+        copied = target.end();
+    }
+    if copied == 0 {
+        return None;
+    }
+    out.push_str(&content[copied..]);
+    Some(out)
+}
+
+/// Rewrite `[[wikilinks]]` in `content` (the text of the note at `file_path`) that
+/// resolve to `old_path` so they resolve to `new_path`. `file_paths` are the vault's
+/// notes before the move. A link written as a name keeps being a name if that is
+/// enough to find the note; otherwise it becomes the vault-relative path. `.md`,
+/// `#heading`, `^block-id` and `|alias` are kept. Returns None if nothing changed.
+pub fn retarget_wikilinks(
+    content: &str,
+    file_path: &str,
+    old_path: &str,
+    new_path: &str,
+    file_paths: &[String],
+) -> Option<String> {
+    let old_key = link_key(old_path);
+    let new_file_path = if link_key(file_path) == old_key {
+        new_path
+    } else {
+        file_path
+    };
+    let new_paths: Vec<String> = file_paths
+        .iter()
+        .map(|p| {
+            if link_key(p) == old_key {
+                new_path.to_string()
+            } else {
+                p.clone()
+            }
+        })
+        .collect();
+    let new_key = link_key(new_path);
+    // Like `link_key`, but keeping the case of the file name.
+    let new_full = new_path.replace('\\', "/");
+    let new_full = new_full.strip_suffix(".md").unwrap_or(&new_full);
+    let new_name = new_full.rsplit('/').next().unwrap_or(new_full);
+    let finds_new = |candidate: &str| {
+        resolve_wikilink(candidate, new_file_path, &new_paths)
+            .map(link_key)
+            .as_ref()
+            == Some(&new_key)
+    };
+
+    let mut out = String::with_capacity(content.len());
+    let mut copied = 0;
+    for c in wikilink_regex().captures_iter(content) {
+        let inner = c.get(1).unwrap();
+        let end = inner
+            .as_str()
+            .find(['#', '^'])
+            .unwrap_or(inner.as_str().len());
+        let raw = &inner.as_str()[..end];
+        let target = raw.trim();
+        if target.is_empty() {
+            continue;
+        }
+        let resolved = resolve_wikilink(target, file_path, file_paths);
+        if resolved.map(link_key).as_deref() != Some(old_key.as_str()) {
+            continue;
+        }
+        let mut new_target = if !target.contains('/') && finds_new(new_name) {
+            new_name.to_string()
+        } else {
+            new_full.to_string()
+        };
+        if target.to_lowercase().ends_with(".md") {
+            new_target.push_str(".md");
+        }
+        let start = inner.start() + (raw.len() - raw.trim_start().len());
+        out.push_str(&content[copied..start]);
+        out.push_str(&new_target);
+        copied = start + target.len();
+    }
+    if copied == 0 {
+        return None;
+    }
+    out.push_str(&content[copied..]);
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(target: &str, heading: Option<&str>, block_id: Option<&str>) -> WikiLink {
+        WikiLink {
+            target: target.into(),
+            heading: heading.map(Into::into),
+            block_id: block_id.map(Into::into),
+            alias: None,
+        }
+    }
+
+    #[test]
+    fn wikilinks_are_parsed() {
+        let links = extract_wikilinks(
+            "See [[Plan]], [[projects/db.md#Open questions]], [[db^pg-choice]], \
+             [[db#^pg-choice|why]], [[#Goals]], [[^x]] but not [[]] or [[ # ]] or [single]",
+        );
+        let mut aliased = link("db", None, Some("pg-choice"));
+        aliased.alias = Some("why".into());
+        assert_eq!(
+            links,
+            vec![
+                link("Plan", None, None),
+                link("projects/db.md", Some("Open questions"), None),
+                link("db", None, Some("pg-choice")),
+                aliased,
+                link("", Some("Goals"), None),
+                link("", None, Some("x")),
+            ]
+        );
+    }
+
+    #[test]
+    fn wikilinks_resolve_by_path_then_folder_then_name() {
+        let files: Vec<String> = [
+            "plan.md",
+            "projects/plan.md",
+            "projects/db.md",
+            "archive/2024/db.md",
+            "archive/db.md",
+            "Notes/Daily.md",
+        ]
+        .iter()
+        .map(|p| p.replace('/', std::path::MAIN_SEPARATOR_STR))
+        .collect();
+        let resolve = |target: &str, from: &str| {
+            resolve_wikilink(target, from, &files).map(|p| p.replace('\\', "/"))
+        };
+        // A vault-relative path wins over the linking file's folder.
+        assert_eq!(resolve("plan", "projects/x.md").as_deref(), Some("plan.md"));
+        assert_eq!(
+            resolve("projects/plan.md", "x.md").as_deref(),
+            Some("projects/plan.md")
+        );
+        assert_eq!(
+            resolve("db", "projects/x.md").as_deref(),
+            Some("projects/db.md")
+        );
+        assert_eq!(
+            resolve("../plan", "projects/x.md").as_deref(),
+            Some("plan.md")
+        );
+        // By name: fewest folders first. Case and `.md` do not matter.
+        assert_eq!(resolve("DB.md", "x.md").as_deref(), Some("archive/db.md"));
+        assert_eq!(
+            resolve("2024/db", "x.md").as_deref(),
+            Some("archive/2024/db.md")
+        );
+        assert_eq!(resolve("daily", "x.md").as_deref(), Some("Notes/Daily.md"));
+        assert_eq!(
+            resolve("", "projects/db.md").as_deref(),
+            Some("projects/db.md")
+        );
+        assert_eq!(resolve("missing", "x.md"), None);
+        assert_eq!(resolve("lan", "x.md"), None);
+    }
+
+    #[test]
+    fn relative_links_follow_the_moved_file() {
+        let content = "- See [plan](../projects/plan.md#goals) and [web](https://x.io/plan.md)\n\
+                       - Other [o](other.md) [root](/projects/plan.md)";
+        let out = retarget_links(
+            content,
+            "notes/a.md",
+            "projects/plan.md",
+            "archive/2024/plan.md",
+        )
+        .unwrap();
+        assert_eq!(
+            out,
+            "- See [plan](../archive/2024/plan.md#goals) and [web](https://x.io/plan.md)\n\
+             - Other [o](other.md) [root](/archive/2024/plan.md)"
+        );
+        assert!(retarget_links(content, "notes/a.md", "notes/plan.md", "x.md").is_none());
+    }
+
+    #[test]
+    fn wikilinks_follow_the_moved_note() {
+        let files: Vec<String> = ["plan.md", "notes/a.md", "projects/db.md", "archive/db.md"]
+            .map(String::from)
+            .to_vec();
+        let content = "- [[plan]] [[Plan.md#Goals|goals]] [[plan^x]] [[ plan #^y]]\n\
+                       - [[planning]] [[#plan]] [[db]] [plan](../plan.md)";
+        let out = retarget_wikilinks(
+            content,
+            "notes/a.md",
+            "plan.md",
+            "archive/Roadmap.md",
+            &files,
+        )
+        .unwrap();
+        assert_eq!(
+            out,
+            "- [[Roadmap]] [[Roadmap.md#Goals|goals]] [[Roadmap^x]] [[ Roadmap #^y]]\n\
+             - [[planning]] [[#plan]] [[db]] [plan](../plan.md)"
+        );
+        // A path stays a path; a name that would find another note becomes one.
+        let out = retarget_wikilinks(
+            "- [[projects/db]] [[db]]",
+            "projects/x.md",
+            "projects/db.md",
+            "old/db.md",
+            &files,
+        )
+        .unwrap();
+        assert_eq!(out, "- [[old/db]] [[old/db]]");
+        assert!(retarget_wikilinks(content, "notes/a.md", "x.md", "y.md", &files).is_none());
+    }
+
+    #[test]
+    fn relative_path_between_folders() {
+        assert_eq!(relative_path("", "a/b.md"), "a/b.md");
+        assert_eq!(relative_path("a", "a/b.md"), "b.md");
+        assert_eq!(relative_path("a/x", "a/b.md"), "../b.md");
+        assert_eq!(relative_path("c", "b.md"), "../b.md");
+        assert_eq!(relative_path("a", "a.md"), "../a.md");
+    }
+}
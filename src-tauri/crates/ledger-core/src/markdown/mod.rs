@@ -0,0 +1,863 @@
+//! Parse markdown list items into a tree of nodes (depth, text, tags, parent/child).
+//! Uses pulldown-cmark; list nesting gives depth. Tags: `#[\w-]+` in list item text,
+//! optionally nested with `/` (`#project/alpha/api`).
+//! Every node carries source spans so the frontend can jump to it in the file.
+//! Task items: GFM `[ ]` / `[x]` markers, plus `[-]` for cancelled.
+//!
+//! Node identity: `id` is the index within one parse. `stable_id` survives edits:
+//! it is the item's `^block-id` marker when present, else a hash of the item's own
+//! text (`h:` prefix), so editing a parent leaves its children's ids alone. Items with
+//! the same text are told apart by their order in the file (`-2`, `-3`, ...).
+
+use pulldown_cmark::{Event, Options, Parser, Tag};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::sync::OnceLock;
+
+/// Compile a regex once per process; the parser runs for every file on every query.
+macro_rules! static_regex {
+    ($re:expr) => {{
+        static RE: OnceLock<Regex> = OnceLock::new();
+        RE.get_or_init(|| Regex::new($re).unwrap())
+    }};
+}
+
+pub mod links;
+
+/// A location in the source: byte offset plus 0-based line and column.
+/// `column` counts characters (not bytes) from the start of the line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Half-open source range `[start, end)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceSpan {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// Checkbox state of a task list item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskState {
+    Open,
+    Done,
+    Cancelled,
+}
+
+impl TaskState {
+    fn marker(self) -> &'static str {
+        match self {
+            TaskState::Open => "[ ]",
+            TaskState::Done => "[x]",
+            TaskState::Cancelled => "[-]",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeNode {
+    pub id: usize,
+    pub depth: u32,
+    pub text: String,
+    pub tags: Vec<String>,
+    pub parent_id: Option<usize>,
+    #[serde(rename = "children_ids")]
+    pub children_ids: Vec<usize>,
+    /// Whole list item, from the bullet marker to the end of its last child.
+    pub span: SourceSpan,
+    /// The item's own text: the inline content of its first block, from after the
+    /// marker to the end of its last link, code span or word (no nested items).
+    pub text_span: SourceSpan,
+    /// Explicit `^block-id` written at the end of the item text, without the `^`.
+    pub block_id: Option<String>,
+    /// Identifier that survives insertions and restarts (see module docs).
+    pub stable_id: String,
+    /// Checkbox state; `None` for plain list items. The marker is not part of `text`.
+    pub task: Option<TaskState>,
+    /// Source range of the checkbox marker (`[ ]`, `[x]` or `[-]`); `None` for plain
+    /// list items.
+    pub task_span: Option<SourceSpan>,
+    /// Inline `key:: value` fields. Keys are lower-cased; values run to the next key.
+    pub attributes: BTreeMap<String, String>,
+    /// `[[wikilinks]]` in the item text, in order (see `links`).
+    pub links: Vec<links::WikiLink>,
+}
+
+/// Maps byte offsets to line/column positions.
+struct LineIndex<'a> {
+    content: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    fn new(content: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(content.match_indices('\n').map(|(i, _)| i + 1));
+        LineIndex {
+            content,
+            line_starts,
+        }
+    }
+
+    fn position(&self, offset: usize) -> Position {
+        let line = self.line_starts.partition_point(|&s| s <= offset) - 1;
+        let line_start = self.line_starts[line];
+        let column = self.content[line_start..offset].chars().count();
+        Position {
+            offset,
+            line,
+            column,
+        }
+    }
+
+    fn span(&self, range: Range<usize>) -> SourceSpan {
+        SourceSpan {
+            start: self.position(range.start),
+            end: self.position(range.end),
+        }
+    }
+}
+
+/// A list item as seen by the event walk, before empty items are dropped and ids assigned.
+struct RawItem {
+    depth: u32,
+    parent: Option<usize>,
+    range: Range<usize>,
+    text: String,
+    /// Source of the inline content of the item's first block (links, code and
+    /// emphasis included).
+    text_range: Option<Range<usize>>,
+    /// Set once the first block has ended; later text (more paragraphs, nested
+    /// lists) is not part of the item's own text.
+    text_done: bool,
+    task: Option<TaskState>,
+    /// Source of the `[ ]` / `[x]` marker.
+    task_range: Option<Range<usize>>,
+}
+
+impl RawItem {
+    /// Extend the text range over an inline event at `range`.
+    fn cover(&mut self, range: Range<usize>) {
+        self.text_range = Some(match self.text_range.take() {
+            Some(r) => r.start.min(range.start)..r.end.max(range.end),
+            None => range,
+        });
+    }
+
+    /// A block started or ended inside the item: the first block is over once it
+    /// has produced some text.
+    fn block_boundary(&mut self) {
+        if self.text_range.is_some() {
+            self.text_done = true;
+        }
+    }
+}
+
+fn tag_regex() -> &'static Regex {
+    static_regex!(r#"#([\w-]+(?:/[\w-]+)*)"#)
+}
+
+/// Tag matches in `text` that are not inside a `[[wikilink]]`.
+fn tag_captures(text: &str) -> impl Iterator<Item = regex::Captures<'_>> {
+    let links = links::wikilink_ranges(text);
+    tag_regex().captures_iter(text).filter(move |c| {
+        let start = c.get(0).unwrap().start();
+        !links.iter().any(|l| l.contains(&start))
+    })
+}
+
+/// Extract tag names from text (pattern #[\w-]+ with `/`-separated segments), without the #.
+fn extract_tags(text: &str) -> Vec<String> {
+    tag_captures(text)
+        .filter_map(|c| c.get(1))
+        .map(|m| m.as_str().to_string())
+        .collect()
+}
+
+/// Extract inline `key:: value` attributes from text. A value runs until the next
+/// `key::` or the end of the text; `::` must be followed by whitespace, so code such as
+/// `std::fs` is not mistaken for an attribute.
+fn extract_attributes(text: &str) -> BTreeMap<String, String> {
+    let re = static_regex!(r"([\w-]+)::");
+    let keys: Vec<(String, usize, usize)> = re
+        .captures_iter(text)
+        .map(|c| c.get(0).unwrap())
+        .filter(|m| {
+            let before = text[..m.start()].chars().next_back();
+            let after = text[m.end()..].chars().next();
+            before.map_or(true, char::is_whitespace) && after.map_or(true, char::is_whitespace)
+        })
+        .map(|m| {
+            (
+                text[m.start()..m.end() - 2].to_lowercase(),
+                m.start(),
+                m.end(),
+            )
+        })
+        .collect();
+    let mut attributes = BTreeMap::new();
+    for (i, (key, _, value_start)) in keys.iter().enumerate() {
+        let value_end = keys.get(i + 1).map_or(text.len(), |next| next.1);
+        let value = text[*value_start..value_end].trim();
+        if !value.is_empty() {
+            attributes.insert(key.clone(), value.to_string());
+        }
+    }
+    attributes
+}
+
+/// True if `tag` is `query` or one of its hierarchical descendants
+/// (`project` matches `project` and `project/alpha/api`, not `projects`).
+pub fn tag_matches(tag: &str, query: &str) -> bool {
+    match tag.strip_prefix(query) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+/// Split a trailing ` ^block-id` marker off item text.
+fn split_block_id(text: &str) -> (String, Option<String>) {
+    let re = static_regex!(r"(?:^|\s)\^([A-Za-z0-9-]+)$");
+    match re.captures(text) {
+        Some(c) => {
+            let whole = c.get(0).unwrap();
+            let id = c.get(1).unwrap().as_str().to_string();
+            (text[..whole.start()].trim_end().to_string(), Some(id))
+        }
+        None => (text.to_string(), None),
+    }
+}
+
+/// 64-bit FNV-1a. Used instead of `DefaultHasher` so hashes stay the same across
+/// Rust versions and restarts.
+fn fnv1a(seed: u64, bytes: &[u8]) -> u64 {
+    let mut hash = seed;
+    for b in bytes {
+        hash ^= u64::from(*b);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Stable hex hash of a file's content, used to detect edits between reading a file
+/// and writing it back.
+pub fn content_hash(content: &str) -> String {
+    format!("{:016x}", fnv1a(FNV_OFFSET, content.as_bytes()))
+}
+
+/// Find a node by `stable_id`, falling back to the per-parse numeric `id`.
+pub fn find_node<'a>(nodes: &'a [TreeNode], node_id: &str) -> Option<&'a TreeNode> {
+    nodes.iter().find(|n| n.stable_id == node_id).or_else(|| {
+        let id: usize = node_id.parse().ok()?;
+        nodes.iter().find(|n| n.id == id)
+    })
+}
+
+/// Pick a short block id for `node` that no other node in `nodes` already uses.
+pub fn new_block_id(nodes: &[TreeNode], node: &TreeNode) -> String {
+    let hex = format!("{:016x}", fnv1a(FNV_OFFSET, node.stable_id.as_bytes()));
+    (6..hex.len())
+        .map(|len| hex[..len].to_string())
+        .find(|candidate| !nodes.iter().any(|n| n.stable_id == *candidate))
+        .unwrap_or(hex)
+}
+
+/// Append a ` ^block_id` marker to the node's text in `content`.
+pub fn insert_block_id(content: &str, node: &TreeNode, block_id: &str) -> String {
+    let at = node.text_span.end.offset;
+    format!("{} ^{}{}", &content[..at], block_id, &content[at..])
+}
+
+/// Rewrite the node's checkbox marker to `state`. `None` if the node is not a task.
+pub fn set_task_state(content: &str, node: &TreeNode, state: TaskState) -> Option<String> {
+    let marker = node.task_span?;
+    Some(format!(
+        "{}{}{}",
+        &content[..marker.start.offset],
+        state.marker(),
+        &content[marker.end.offset..]
+    ))
+}
+
+/// True if `name` is a valid tag name (without the #).
+pub fn is_valid_tag(name: &str) -> bool {
+    static_regex!(r"^[\w-]+(?:/[\w-]+)*$").is_match(name)
+}
+
+/// Result of rewriting one tag in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagRewrite {
+    pub content: String,
+    /// List items whose text changed.
+    pub nodes: usize,
+    /// Tag occurrences renamed or removed.
+    pub occurrences: usize,
+}
+
+/// Rename tag `from` to `to` in the text of every list item in `content`. With
+/// `include_children`, `from/x` becomes `to/x` as well. If an item ends up with the same
+/// tag twice (merging into a tag it already has), later copies are removed. Returns None
+/// if nothing changed.
+pub fn rename_tag(
+    content: &str,
+    nodes: &[TreeNode],
+    from: &str,
+    to: &str,
+    include_children: bool,
+) -> Option<TagRewrite> {
+    let texts = text_ranges(content);
+    // (range, replacement) edits in document order.
+    let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+    let mut changed_nodes = 0;
+    for node in nodes {
+        let span = node.text_span.start.offset..node.text_span.end.offset;
+        // (range of "#tag" in content, final name, renamed?)
+        let tags: Vec<(Range<usize>, String, bool)> = texts
+            .iter()
+            .filter(|r| r.start < span.end && span.start < r.end)
+            .flat_map(|r| {
+                let base = r.start.max(span.start);
+                tag_captures(&content[base..r.end.min(span.end)]).map(move |c| (base, c))
+            })
+            .map(|(base, c)| {
+                let whole = c.get(0).unwrap();
+                let name = c.get(1).unwrap().as_str();
+                let renamed = if name == from {
+                    Some(to.to_string())
+                } else if include_children && tag_matches(name, from) {
+                    Some(format!("{}{}", to, &name[from.len()..]))
+                } else {
+                    None
+                };
+                let range = base + whole.start()..base + whole.end();
+                match renamed {
+                    Some(n) => (range, n, true),
+                    None => (range, name.to_string(), false),
+                }
+            })
+            .collect();
+        let before = edits.len();
+        for (i, (range, name, renamed)) in tags.iter().enumerate() {
+            let duplicate = tags[..i].iter().any(|(_, earlier, earlier_renamed)| {
+                earlier == name && (*renamed || *earlier_renamed)
+            });
+            if duplicate {
+                // Drop the tag together with one space before it.
+                let start = if content[..range.start].ends_with(' ') {
+                    range.start - 1
+                } else {
+                    range.start
+                };
+                edits.push((start..range.end, String::new()));
+            } else if *renamed {
+                edits.push((range.clone(), format!("#{}", name)));
+            }
+        }
+        if edits.len() > before {
+            changed_nodes += 1;
+        }
+    }
+    if edits.is_empty() {
+        return None;
+    }
+    let mut out = String::with_capacity(content.len());
+    let mut copied = 0;
+    for (range, replacement) in &edits {
+        out.push_str(&content[copied..range.start]);
+        out.push_str(replacement);
+        copied = range.end;
+    }
+    out.push_str(&content[copied..]);
+    Some(TagRewrite {
+        content: out,
+        nodes: changed_nodes,
+        occurrences: edits.len(),
+    })
+}
+
+/// Byte ranges of plain text in `content`, with adjacent text events merged. Code spans,
+/// code blocks, HTML and link destinations are not included.
+fn text_ranges(content: &str) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for (event, range) in Parser::new_ext(content, Options::ENABLE_TASKLISTS).into_offset_iter() {
+        if let Event::Text(_) = event {
+            match ranges.last_mut() {
+                Some(last) if last.end == range.start => last.end = range.end,
+                _ => ranges.push(range),
+            }
+        }
+    }
+    ranges
+}
+
+/// Parse markdown content into tree nodes. Depth from list nesting in the parser.
+/// Items are created at `Start(Item)` so parents always precede their children;
+/// text is attributed to the innermost open item, up to the end of its first block.
+pub fn parse_list_items(content: &str) -> Vec<TreeNode> {
+    let lines = LineIndex::new(content);
+    let mut items: Vec<RawItem> = Vec::new();
+    let mut open: Vec<usize> = Vec::new(); // items whose End(Item) has not been seen yet
+    let mut list_depth: u32 = 0;
+
+    let parser = Parser::new_ext(content, Options::ENABLE_TASKLISTS).into_offset_iter();
+
+    for (event, range) in parser {
+        let item = open.last().and_then(|&i| items.get_mut(i));
+        match event {
+            Event::Start(Tag::List(_)) => {
+                if let Some(item) = item {
+                    item.text_done = true;
+                }
+                list_depth = list_depth.saturating_add(1);
+            }
+            Event::End(Tag::List(_)) => {
+                list_depth = list_depth.saturating_sub(1);
+            }
+            Event::Start(Tag::Item) => {
+                items.push(RawItem {
+                    depth: list_depth.saturating_sub(1), // depth 0 = top-level list
+                    parent: open.last().copied(),
+                    range,
+                    text: String::new(),
+                    text_range: None,
+                    text_done: false,
+                    task: None,
+                    task_range: None,
+                });
+                open.push(items.len() - 1);
+            }
+            Event::End(Tag::Item) => {
+                open.pop();
+            }
+            _ => {
+                let Some(item) = item.filter(|i| !i.text_done) else {
+                    continue;
+                };
+                match event {
+                    Event::TaskListMarker(checked) => {
+                        item.task = Some(if checked {
+                            TaskState::Done
+                        } else {
+                            TaskState::Open
+                        });
+                        item.task_range = Some(range);
+                    }
+                    Event::Start(tag) | Event::End(tag) if !is_inline(&tag) => {
+                        item.block_boundary();
+                    }
+                    Event::Start(_) | Event::End(_) => item.cover(range),
+                    Event::Text(t) => {
+                        item.text.push_str(&t);
+                        item.cover(range);
+                    }
+                    Event::SoftBreak => item.text.push(' '),
+                    Event::HardBreak => item.text.push('\n'),
+                    _ => item.cover(range),
+                }
+            }
+        }
+    }
+
+    build_nodes(content, &lines, items)
+}
+
+/// Tags that wrap inline content, as opposed to blocks.
+fn is_inline(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link(..) | Tag::Image(..)
+    )
+}
+
+/// Drop items without text and assign ids in document order. Children of a dropped
+/// item are attached to its nearest kept ancestor.
+fn build_nodes(content: &str, lines: &LineIndex, items: Vec<RawItem>) -> Vec<TreeNode> {
+    let mut nodes: Vec<TreeNode> = Vec::new();
+    let mut id_of: Vec<Option<usize>> = Vec::with_capacity(items.len());
+    let raw_parents: Vec<Option<usize>> = items.iter().map(|i| i.parent).collect();
+    let mut seen_hashes: HashMap<u64, usize> = HashMap::new();
+
+    for item in items {
+        let mut text_range = item
+            .text_range
+            .clone()
+            .unwrap_or(item.range.start..item.range.start);
+        let mut task = item.task;
+        let mut task_range = item.task_range.clone();
+        let mut raw_text = item.text.trim();
+        // `[-]` is not GFM, so pulldown-cmark leaves it in the text.
+        if task.is_none() && content[text_range.start..].starts_with("[-]") {
+            if let Some(rest) = raw_text.strip_prefix("[-]") {
+                if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+                    let rest = rest.trim_start();
+                    task_range = Some(text_range.start..text_range.start + 3);
+                    text_range.start += raw_text.len() - rest.len();
+                    raw_text = rest;
+                    task = Some(TaskState::Cancelled);
+                }
+            }
+        }
+        let (text, block_id) = split_block_id(raw_text);
+        if text.is_empty() {
+            id_of.push(None);
+            continue;
+        }
+        let mut parent_id = None;
+        let mut ancestor = item.parent;
+        while let Some(a) = ancestor {
+            if let Some(pid) = id_of[a] {
+                parent_id = Some(pid);
+                break;
+            }
+            ancestor = raw_parents[a];
+        }
+        let id = nodes.len();
+        if let Some(pid) = parent_id {
+            nodes[pid].children_ids.push(id);
+        }
+        let text_hash = fnv1a(FNV_OFFSET, text.as_bytes());
+        // Identical items are told apart by occurrence.
+        let occurrence = seen_hashes.entry(text_hash).or_insert(0);
+        *occurrence += 1;
+        let stable_id = match &block_id {
+            Some(b) => b.clone(),
+            None if *occurrence == 1 => format!("h:{:016x}", text_hash),
+            None => format!("h:{:016x}-{}", text_hash, occurrence),
+        };
+        let end = item.range.start + content[item.range.clone()].trim_end().len();
+        nodes.push(TreeNode {
+            id,
+            depth: item.depth,
+            tags: extract_tags(&text),
+            attributes: extract_attributes(&text),
+            links: links::extract_wikilinks(&text),
+            text,
+            parent_id,
+            children_ids: Vec::new(),
+            span: lines.span(item.range.start..end),
+            text_span: lines.span(text_range),
+            block_id,
+            stable_id,
+            task,
+            task_span: task_range.map(|r| lines.span(r)),
+        });
+        id_of.push(Some(id));
+    }
+
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_two_root_one_child() {
+        // Nested list: first item contains a sublist
+        let content = "- a\n  - b";
+        let nodes = parse_list_items(content);
+        assert_eq!(nodes.len(), 2, "expected 2 nodes");
+        assert_eq!(nodes[0].depth, 0);
+        assert_eq!(nodes[0].parent_id, None);
+        assert!(nodes[0].text.contains("a"));
+        assert_eq!(nodes[1].depth, 1);
+        assert_eq!(nodes[1].parent_id, Some(0));
+        assert!(nodes[1].text.contains("b"));
+        assert_eq!(nodes[0].children_ids, vec![1]);
+    }
+
+    #[test]
+    fn parse_tags_decision_ci() {
+        let content = "- Do it #decision #ci";
+        let nodes = parse_list_items(content);
+        assert_eq!(nodes.len(), 1);
+        assert!(nodes[0].tags.contains(&"decision".to_string()));
+        assert!(nodes[0].tags.contains(&"ci".to_string()));
+    }
+
+    #[test]
+    fn spans_cover_item_and_text() {
+        let content = "# Notes\n\n- Ship it #decision\n  - Rollback plan\n- Next";
+        let nodes = parse_list_items(content);
+        assert_eq!(nodes.len(), 3);
+
+        let first = &nodes[0];
+        assert_eq!(first.span.start.offset, 9);
+        assert_eq!(first.span.start.line, 2);
+        assert_eq!(first.span.start.column, 0);
+        assert_eq!(
+            &content[first.text_span.start.offset..first.text_span.end.offset],
+            "Ship it #decision"
+        );
+        assert_eq!(first.text_span.start.column, 2);
+        // Parent span runs to the end of its nested child.
+        assert_eq!(first.span.end.line, 3);
+        assert_eq!(
+            &content[first.span.end.offset - 4..first.span.end.offset],
+            "plan"
+        );
+
+        let child = &nodes[1];
+        assert_eq!(child.span.start.line, 3);
+        assert_eq!(child.span.start.column, 2);
+        assert_eq!(
+            &content[child.text_span.start.offset..child.text_span.end.offset],
+            "Rollback plan"
+        );
+
+        assert_eq!(nodes[2].text_span.start.line, 4);
+    }
+
+    #[test]
+    fn span_columns_count_characters() {
+        let content = "- Größe prüfen #ux";
+        let nodes = parse_list_items(content);
+        assert_eq!(nodes[0].text_span.start.column, 2);
+        assert_eq!(nodes[0].text_span.end.column, content.chars().count());
+        assert_eq!(nodes[0].text_span.end.offset, content.len());
+    }
+
+    #[test]
+    fn block_id_marker_is_split_from_text() {
+        let nodes = parse_list_items("- Adopt Postgres #decision ^db-choice\n- Plain");
+        assert_eq!(nodes[0].text, "Adopt Postgres #decision");
+        assert_eq!(nodes[0].block_id.as_deref(), Some("db-choice"));
+        assert_eq!(nodes[0].stable_id, "db-choice");
+        assert_eq!(nodes[1].block_id, None);
+        assert!(nodes[1].stable_id.starts_with("h:"));
+    }
+
+    #[test]
+    fn stable_ids_survive_insertions_above() {
+        let before = parse_list_items("- A\n  - child\n- B\n  - child");
+        let after = parse_list_items("- New\n- A\n  - child\n- B\n  - child");
+        assert_eq!(before[1].stable_id, after[2].stable_id);
+        assert_eq!(before[3].stable_id, after[4].stable_id);
+        // Same text under different parents gets different ids.
+        assert_ne!(before[1].stable_id, before[3].stable_id);
+    }
+
+    #[test]
+    fn stable_ids_survive_parent_edits() {
+        let before = parse_list_items(
+            "- Plan
+  - child
+    - grandchild",
+        );
+        let after = parse_list_items(
+            "- Plan v2
+  - child
+    - grandchild",
+        );
+        assert_ne!(before[0].stable_id, after[0].stable_id);
+        assert_eq!(before[1].stable_id, after[1].stable_id);
+        assert_eq!(before[2].stable_id, after[2].stable_id);
+        // Known limit: a new item with the same text above shifts the occurrence suffix.
+        let before = parse_list_items(
+            "- A
+  - child",
+        );
+        let after = parse_list_items(
+            "- B
+  - child
+- A
+  - child",
+        );
+        assert_ne!(before[1].stable_id, after[3].stable_id);
+    }
+
+    #[test]
+    fn duplicate_siblings_get_distinct_ids() {
+        let nodes = parse_list_items("- same\n- same");
+        assert_ne!(nodes[0].stable_id, nodes[1].stable_id);
+    }
+
+    #[test]
+    fn text_span_ends_after_trailing_inline_syntax() {
+        for (content, expected) in [
+            (
+                "- See [docs](http://x.io)\n  - child",
+                "- See [docs](http://x.io) ^abc\n  - child",
+            ),
+            ("- Run `cargo test`", "- Run `cargo test` ^abc"),
+            ("- Ship it **now**\n", "- Ship it **now** ^abc\n"),
+        ] {
+            let nodes = parse_list_items(content);
+            assert_eq!(insert_block_id(content, &nodes[0], "abc"), expected);
+            assert_eq!(
+                parse_list_items(expected)[0].block_id.as_deref(),
+                Some("abc")
+            );
+        }
+        let content = "- [ ] [docs](http://x.io) review";
+        let nodes = parse_list_items(content);
+        assert_eq!(
+            &content[nodes[0].text_span.start.offset..nodes[0].text_span.end.offset],
+            "[docs](http://x.io) review"
+        );
+    }
+
+    #[test]
+    fn later_paragraphs_are_not_item_text() {
+        let content = "- first\n\n  second paragraph #later\n- next";
+        let nodes = parse_list_items(content);
+        assert_eq!(nodes[0].text, "first");
+        assert!(nodes[0].tags.is_empty());
+        assert_eq!(nodes[0].text_span.end.offset, 7);
+        assert_eq!(nodes[1].text, "next");
+    }
+
+    #[test]
+    fn insert_block_id_appends_marker_to_item_text() {
+        let content = "- Ship it #decision\n  - detail\n";
+        let nodes = parse_list_items(content);
+        let updated = insert_block_id(content, &nodes[0], "ship");
+        assert_eq!(updated, "- Ship it #decision ^ship\n  - detail\n");
+        let reparsed = parse_list_items(&updated);
+        assert_eq!(find_node(&reparsed, "ship").map(|n| n.id), Some(0));
+        assert_eq!(reparsed[1].stable_id, nodes[1].stable_id);
+    }
+
+    #[test]
+    fn task_markers_set_task_state() {
+        let content = "- [ ] open #todo\n- [x] done\n- [-] dropped\n- plain [ ] text";
+        let nodes = parse_list_items(content);
+        assert_eq!(nodes[0].task, Some(TaskState::Open));
+        assert_eq!(nodes[0].text, "open #todo");
+        assert_eq!(nodes[1].task, Some(TaskState::Done));
+        assert_eq!(nodes[2].task, Some(TaskState::Cancelled));
+        assert_eq!(nodes[2].text, "dropped");
+        assert_eq!(
+            &content[nodes[2].text_span.start.offset..nodes[2].text_span.end.offset],
+            "dropped"
+        );
+        assert_eq!(nodes[3].task, None);
+    }
+
+    #[test]
+    fn set_task_state_rewrites_only_the_marker() {
+        let content = "- [ ] ship #ci\n  - [x] build [ ] step\n- [-] old\n";
+        let nodes = parse_list_items(content);
+        let updated = set_task_state(content, &nodes[0], TaskState::Done).unwrap();
+        assert_eq!(
+            updated,
+            "- [x] ship #ci\n  - [x] build [ ] step\n- [-] old\n"
+        );
+        let updated = set_task_state(content, &nodes[1], TaskState::Open).unwrap();
+        assert_eq!(
+            updated,
+            "- [ ] ship #ci\n  - [ ] build [ ] step\n- [-] old\n"
+        );
+        let updated = set_task_state(content, &nodes[2], TaskState::Open).unwrap();
+        assert_eq!(
+            updated,
+            "- [ ] ship #ci\n  - [x] build [ ] step\n- [ ] old\n"
+        );
+
+        // The marker, not the `[` of a link at the start of the text.
+        let content = "- [ ] [docs](http://x.io) review\n- [-] [old](a.md)";
+        let nodes = parse_list_items(content);
+        assert_eq!(
+            set_task_state(content, &nodes[0], TaskState::Done).unwrap(),
+            "- [x] [docs](http://x.io) review\n- [-] [old](a.md)"
+        );
+        assert_eq!(
+            set_task_state(content, &nodes[1], TaskState::Open).unwrap(),
+            "- [ ] [docs](http://x.io) review\n- [ ] [old](a.md)"
+        );
+
+        let plain = parse_list_items("- plain");
+        assert!(set_task_state("- plain", &plain[0], TaskState::Done).is_none());
+    }
+
+    #[test]
+    fn hierarchical_tags_keep_all_segments() {
+        let nodes = parse_list_items("- API cut #project/alpha/api #ci/ #x//y");
+        assert_eq!(nodes[0].tags, vec!["project/alpha/api", "ci", "x"]);
+    }
+
+    #[test]
+    fn wikilinks_are_parsed_and_not_tags() {
+        let nodes = parse_list_items("- Follows [[db#Options]] and [[db^pg]] #decision ^next");
+        assert_eq!(nodes[0].tags, vec!["decision"]);
+        assert_eq!(nodes[0].block_id.as_deref(), Some("next"));
+        let links = &nodes[0].links;
+        let targets: Vec<_> = links
+            .iter()
+            .map(|l| (&*l.target, l.heading.as_deref(), l.block_id.as_deref()))
+            .collect();
+        assert_eq!(
+            targets,
+            vec![("db", Some("Options"), None), ("db", None, Some("pg"))]
+        );
+    }
+
+    #[test]
+    fn tag_matches_descendants_on_segment_boundary() {
+        assert!(tag_matches("project", "project"));
+        assert!(tag_matches("project/alpha/api", "project"));
+        assert!(tag_matches("project/alpha/api", "project/alpha"));
+        assert!(!tag_matches("projects", "project"));
+        assert!(!tag_matches("project", "project/alpha"));
+    }
+
+    #[test]
+    fn inline_attributes_are_parsed() {
+        let nodes = parse_list_items(
+            "- Adopt Postgres #decision status:: accepted Owner:: alice\n- Use std::fs here\n- due:: 2026-11-01\n- a:: b:: c",
+        );
+        assert_eq!(nodes[0].attributes.get("status").unwrap(), "accepted");
+        assert_eq!(nodes[0].attributes.get("owner").unwrap(), "alice");
+        assert!(nodes[1].attributes.is_empty());
+        assert_eq!(nodes[2].attributes.get("due").unwrap(), "2026-11-01");
+        // An empty value is dropped; the next key still starts a new attribute.
+        assert!(!nodes[3].attributes.contains_key("a"));
+        assert_eq!(nodes[3].attributes.get("b").unwrap(), "c");
+    }
+
+    #[test]
+    fn rename_tag_rewrites_children_and_merges_duplicates() {
+        let content = "- Build #ci\n  - Step #ci/lint #infra\n- Pipeline #ci #build\n- Docs #cis";
+        let nodes = parse_list_items(content);
+
+        let r = rename_tag(content, &nodes, "ci", "build", false).unwrap();
+        assert_eq!(
+            r.content,
+            "- Build #build\n  - Step #ci/lint #infra\n- Pipeline #build\n- Docs #cis"
+        );
+        assert_eq!((r.nodes, r.occurrences), (2, 3));
+
+        let r = rename_tag(content, &nodes, "ci", "build", true).unwrap();
+        assert_eq!(
+            r.content,
+            "- Build #build\n  - Step #build/lint #infra\n- Pipeline #build\n- Docs #cis"
+        );
+        assert_eq!(r.nodes, 3);
+
+        // Tags outside list items are not touched.
+        let content = "# Notes #ci\n\n- none here";
+        assert!(rename_tag(content, &parse_list_items(content), "ci", "build", true).is_none());
+        assert!(is_valid_tag("project/alpha"));
+
+        // Nor are headings in wikilinks.
+        let content = "- See [[plan#ci]] #ci";
+        let r = rename_tag(content, &parse_list_items(content), "ci", "build", false).unwrap();
+        assert_eq!(r.content, "- See [[plan#ci]] #build");
+
+        // Nor are code spans.
+        let content = "- Use `#ci` in config #ci";
+        let r = rename_tag(content, &parse_list_items(content), "ci", "build", false).unwrap();
+        assert_eq!(r.content, "- Use `#ci` in config #build");
+        assert_eq!(r.occurrences, 1);
+        assert!(!is_valid_tag("bad tag") && !is_valid_tag("a//b") && !is_valid_tag(""));
+    }
+}
@@ -0,0 +1,235 @@
+use crate::error::LedgerError;
+use crate::markdown;
+use crate::query_lang;
+use serde::Serialize;
+use std::cmp::Ordering;
+
+use super::vault::Vault;
+
+#[derive(Debug, Serialize)]
+pub struct QueryResultItem {
+    pub file_path: String,
+    pub parent_path: Option<String>,
+    pub node: markdown::TreeNode,
+    /// Tags written on the item itself.
+    pub own_tags: Vec<String>,
+    /// Own tags followed by tags inherited from ancestor items, nearest first.
+    pub effective_tags: Vec<String>,
+}
+
+fn parent_path(nodes: &[markdown::TreeNode], node: &markdown::TreeNode) -> Option<String> {
+    let mut path_parts: Vec<String> = Vec::new();
+    let mut current_id = node.parent_id;
+    while let Some(pid) = current_id {
+        if let Some(p) = nodes.iter().find(|n| n.id == pid) {
+            path_parts.insert(0, p.text.trim().to_string());
+            current_id = p.parent_id;
+        } else {
+            break;
+        }
+    }
+    if path_parts.is_empty() {
+        None
+    } else {
+        Some(path_parts.join(" > "))
+    }
+}
+
+/// Own tags plus every ancestor's tags (walking `parent_id`), without duplicates.
+pub(crate) fn effective_tags(
+    nodes: &[markdown::TreeNode],
+    node: &markdown::TreeNode,
+) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    let mut current = Some(node);
+    while let Some(n) = current {
+        for t in &n.tags {
+            if !tags.contains(t) {
+                tags.push(t.clone());
+            }
+        }
+        current = n
+            .parent_id
+            .and_then(|pid| nodes.iter().find(|p| p.id == pid));
+    }
+    tags
+}
+
+pub(crate) fn result_item(
+    rel_path: &str,
+    nodes: &[markdown::TreeNode],
+    node: &markdown::TreeNode,
+    effective: Vec<String>,
+) -> QueryResultItem {
+    QueryResultItem {
+        file_path: rel_path.to_string(),
+        parent_path: parent_path(nodes, node),
+        node: node.clone(),
+        own_tags: node.tags.clone(),
+        effective_tags: effective,
+    }
+}
+
+/// Stable sort by attribute `key` (see `query_lang::compare_values`). Items without the
+/// attribute go last in either direction.
+fn sort_by_attribute(results: &mut [QueryResultItem], key: &str, descending: bool) {
+    let key = key.to_lowercase();
+    results.sort_by(
+        |a, b| match (a.node.attributes.get(&key), b.node.attributes.get(&key)) {
+            (Some(x), Some(y)) => {
+                let ord = query_lang::compare_values(x, y);
+                if descending {
+                    ord.reverse()
+                } else {
+                    ord
+                }
+            }
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        },
+    );
+}
+
+fn is_descendant(nodes: &[markdown::TreeNode], node_id: usize, ancestor_id: usize) -> bool {
+    let mut current_id = nodes
+        .iter()
+        .find(|n| n.id == node_id)
+        .and_then(|n| n.parent_id);
+    while let Some(pid) = current_id {
+        if pid == ancestor_id {
+            return true;
+        }
+        current_id = nodes.iter().find(|n| n.id == pid).and_then(|n| n.parent_id);
+    }
+    false
+}
+
+impl Vault {
+    /// List items tagged with any of `tag_names` (or a child tag), in file order. With
+    /// `scope_node_id`, only that item and its descendants are considered, in files that
+    /// contain it. With `inherited`, tags on ancestor items count too.
+    pub fn query_by_tag(
+        &mut self,
+        tag_names: &[String],
+        scope_node_id: Option<&str>,
+        paths: Option<&[String]>,
+        task: Option<markdown::TaskState>,
+        inherited: bool,
+    ) -> Vec<QueryResultItem> {
+        let file_list = self.file_list(paths);
+        self.refresh_index(&file_list);
+        let mut results = Vec::new();
+
+        for rel_path in file_list {
+            let nodes = match self.index.get(&rel_path) {
+                Some(entry) => &entry.nodes,
+                None => continue,
+            };
+            // Scope is a stable id (or per-parse id); files without that node are skipped.
+            let scope_id = match scope_node_id {
+                Some(sid) => match markdown::find_node(nodes, sid) {
+                    Some(scope) => Some(scope.id),
+                    None => continue,
+                },
+                None => None,
+            };
+            for node in nodes {
+                let effective = effective_tags(nodes, node);
+                let match_tags = if inherited { &effective } else { &node.tags };
+                let has_tag = match_tags
+                    .iter()
+                    .any(|t| tag_names.iter().any(|q| markdown::tag_matches(t, q)));
+                if !has_tag {
+                    continue;
+                }
+                if task.is_some() && node.task != task {
+                    continue;
+                }
+                if let Some(sid) = scope_id {
+                    if node.id != sid && !is_descendant(nodes, node.id, sid) {
+                        continue;
+                    }
+                }
+                results.push(result_item(&rel_path, nodes, node, effective));
+            }
+        }
+        results
+    }
+
+    /// Run a query-language expression (see `query_lang`) over the vault or `paths`.
+    /// With `inherited`, `#tag` terms also see tags from ancestor items. With `sort_by`,
+    /// results are ordered by that attribute instead of file order.
+    pub fn query(
+        &mut self,
+        query: &str,
+        paths: Option<&[String]>,
+        inherited: bool,
+        sort_by: Option<&str>,
+        descending: bool,
+    ) -> Result<Vec<QueryResultItem>, LedgerError> {
+        let expr = query_lang::parse(query)?;
+        let file_list = self.file_list(paths);
+        self.refresh_index(&file_list);
+        let mut results = Vec::new();
+
+        for rel_path in file_list {
+            let nodes = match self.index.get(&rel_path) {
+                Some(entry) => &entry.nodes,
+                None => continue,
+            };
+            for node in nodes {
+                let effective = effective_tags(nodes, node);
+                let ctx = query_lang::NodeContext {
+                    file_path: &rel_path,
+                    node,
+                    tags: if inherited { &effective } else { &node.tags },
+                };
+                if !expr.matches(&ctx) {
+                    continue;
+                }
+                results.push(result_item(&rel_path, nodes, node, effective));
+            }
+        }
+
+        if let Some(key) = sort_by {
+            sort_by_attribute(&mut results, key, descending);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effective_tags_walk_ancestors_nearest_first() {
+        let nodes = markdown::parse_list_items(
+            "- Auth rewrite #decision #auth\n  - Token format #api\n    - Use JWT #auth",
+        );
+        assert_eq!(effective_tags(&nodes, &nodes[0]), vec!["decision", "auth"]);
+        assert_eq!(
+            effective_tags(&nodes, &nodes[2]),
+            vec!["auth", "api", "decision"]
+        );
+    }
+
+    #[test]
+    fn sort_by_attribute_puts_missing_last() {
+        let nodes = markdown::parse_list_items(
+            "- a due:: 2026-12-01\n- b\n- c due:: 2026-10-01\n- d due:: 2027-01-01",
+        );
+        let texts = |items: &[QueryResultItem]| -> Vec<String> {
+            items.iter().map(|i| i.node.text[..1].to_string()).collect()
+        };
+        let mut items: Vec<QueryResultItem> = nodes
+            .iter()
+            .map(|n| result_item("x.md", &nodes, n, n.tags.clone()))
+            .collect();
+        sort_by_attribute(&mut items, "Due", false);
+        assert_eq!(texts(&items), vec!["c", "a", "d", "b"]);
+        sort_by_attribute(&mut items, "due", true);
+        assert_eq!(texts(&items), vec!["d", "a", "c", "b"]);
+    }
+}
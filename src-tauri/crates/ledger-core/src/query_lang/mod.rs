@@ -0,0 +1,532 @@
+//! Boolean query language over list items.
+//!
+//! Grammar (keywords are upper case; adjacent terms are joined with AND):
+//!
+//! ```text
+//! expr    := and ("OR" and)*
+//! and     := unary ("AND"? unary)*
+//! unary   := "NOT" unary | "(" expr ")" | term
+//! term    := #tag[/sub...] | path:<glob> | task:<open|done|cancelled>
+//!          | key <op> value | "quoted text" | word
+//! op      := = | != | < | <= | > | >=
+//! ```
+//!
+//! `#tag` also matches its hierarchical descendants (`#project` matches `#project/alpha`).
+//! Text terms match the item text case-insensitively. `path:` globs use `*` (within one
+//! folder), `**` (across folders), and `?` (one character).
+//!
+//! `key <op> value` compares the item's `key:: value` attribute. Values that both parse as
+//! numbers compare numerically; anything else compares as case-insensitive text, which
+//! orders ISO dates correctly. Items without the attribute never match a comparison.
+
+use crate::markdown::{tag_matches, TaskState, TreeNode};
+use regex::Regex;
+use std::cmp::Ordering;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    /// Byte offset in the query string where the problem was found.
+    pub offset: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at position {})", self.message, self.offset + 1)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Tag(String),
+    Text(String),
+    Path(Regex),
+    Task(TaskState),
+    Compare(String, CmpOp, String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CmpOp {
+    fn holds(self, ord: Ordering) -> bool {
+        match self {
+            CmpOp::Eq => ord == Ordering::Equal,
+            CmpOp::Ne => ord != Ordering::Equal,
+            CmpOp::Lt => ord == Ordering::Less,
+            CmpOp::Le => ord != Ordering::Greater,
+            CmpOp::Gt => ord == Ordering::Greater,
+            CmpOp::Ge => ord != Ordering::Less,
+        }
+    }
+}
+
+/// Order two attribute values: numerically if both are numbers, else as lower-cased text.
+pub fn compare_values(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(x), Ok(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+        _ => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
+
+/// What an expression is evaluated against: one list item in one file.
+pub struct NodeContext<'a> {
+    pub file_path: &'a str,
+    pub node: &'a TreeNode,
+    /// Tags `#tag` terms are checked against: the node's own tags, or its effective
+    /// tags when inheritance is on.
+    pub tags: &'a [String],
+}
+
+impl Expr {
+    pub fn matches(&self, ctx: &NodeContext) -> bool {
+        match self {
+            Expr::Tag(tag) => ctx.tags.iter().any(|t| tag_matches(t, tag)),
+            Expr::Text(text) => ctx.node.text.to_lowercase().contains(text),
+            Expr::Path(re) => re.is_match(ctx.file_path),
+            Expr::Task(state) => ctx.node.task == Some(*state),
+            Expr::Compare(key, op, value) => match ctx.node.attributes.get(key) {
+                Some(actual) => op.holds(compare_values(actual, value)),
+                None => false,
+            },
+            Expr::Not(e) => !e.matches(ctx),
+            Expr::And(a, b) => a.matches(ctx) && b.matches(ctx),
+            Expr::Or(a, b) => a.matches(ctx) || b.matches(ctx),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Tag(String),
+    Field(String, String),
+    Cmp(CmpOp),
+    /// Bare word (lower-cased); may be the key of a comparison.
+    Word(String),
+    /// Quoted text (lower-cased).
+    Text(String),
+}
+
+fn is_word_char(c: char) -> bool {
+    !c.is_whitespace() && !matches!(c, '(' | ')' | '"' | '=' | '<' | '>')
+}
+
+/// Comparison operator at the start of `rest`, with its length in bytes.
+fn cmp_op(rest: &str) -> Option<(CmpOp, usize)> {
+    [
+        ("!=", CmpOp::Ne),
+        ("<=", CmpOp::Le),
+        (">=", CmpOp::Ge),
+        ("=", CmpOp::Eq),
+        ("<", CmpOp::Lt),
+        (">", CmpOp::Gt),
+    ]
+    .into_iter()
+    .find(|(s, _)| rest.starts_with(s))
+    .map(|(s, op)| (op, s.len()))
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        match c {
+            '(' => {
+                chars.next();
+                tokens.push((Token::LParen, start));
+            }
+            ')' => {
+                chars.next();
+                tokens.push((Token::RParen, start));
+            }
+            _ if cmp_op(&input[start..]).is_some() => {
+                let (op, len) = cmp_op(&input[start..]).unwrap();
+                for _ in 0..len {
+                    chars.next();
+                }
+                tokens.push((Token::Cmp(op), start));
+            }
+            '"' => {
+                chars.next();
+                let mut text = String::new();
+                let mut closed = false;
+                for (_, c) in chars.by_ref() {
+                    if c == '"' {
+                        closed = true;
+                        break;
+                    }
+                    text.push(c);
+                }
+                if !closed {
+                    return Err(ParseError {
+                        message: "Unterminated quoted text".to_string(),
+                        offset: start,
+                    });
+                }
+                tokens.push((Token::Text(text.to_lowercase()), start));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&(i, c)) = chars.peek() {
+                    if !is_word_char(c) || input[i..].starts_with("!=") {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push((word_token(&word, start)?, start));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn word_token(word: &str, offset: usize) -> Result<Token, ParseError> {
+    match word {
+        "AND" => return Ok(Token::And),
+        "OR" => return Ok(Token::Or),
+        "NOT" => return Ok(Token::Not),
+        _ => {}
+    }
+    if let Some(name) = word.strip_prefix('#') {
+        let valid_segment = |s: &str| {
+            !s.is_empty()
+                && s.chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        };
+        if !name.split('/').all(valid_segment) {
+            return Err(ParseError {
+                message: format!("Invalid tag '{}'", word),
+                offset,
+            });
+        }
+        return Ok(Token::Tag(name.to_string()));
+    }
+    if let Some((field, value)) = word.split_once(':') {
+        if value.is_empty() {
+            return Err(ParseError {
+                message: format!("Missing value after '{}:'", field),
+                offset,
+            });
+        }
+        return Ok(Token::Field(field.to_string(), value.to_string()));
+    }
+    Ok(Token::Word(word.to_lowercase()))
+}
+
+/// Convert a path glob to an anchored regex.
+fn glob_to_regex(glob: &str) -> Regex {
+    let mut pattern = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                pattern.push_str(".*");
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).expect("escaped glob is a valid regex")
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |(_, o)| *o)
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            message: message.into(),
+            offset: self.offset(),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            let right = self.parse_and()?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.parse_unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::And) => self.pos += 1,
+                Some(Token::Or) | Some(Token::RParen) | None => break,
+                Some(_) => {} // implicit AND
+            }
+            let right = self.parse_unary()?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, ParseError> {
+        let offset = self.offset();
+        let token = match self.tokens.get(self.pos) {
+            Some((t, _)) => t.clone(),
+            None => return Err(self.error("Expected a term")),
+        };
+        self.pos += 1;
+        match token {
+            Token::Not => Ok(Expr::Not(Box::new(self.parse_unary()?))),
+            Token::LParen => {
+                let inner = self.parse_or()?;
+                if self.peek() != Some(&Token::RParen) {
+                    return Err(self.error("Expected ')'"));
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            Token::Tag(tag) => Ok(Expr::Tag(tag)),
+            Token::Word(word) => match self.peek() {
+                Some(&Token::Cmp(op)) => {
+                    self.pos += 1;
+                    let value = match self.tokens.get(self.pos) {
+                        Some((Token::Word(v), _)) | Some((Token::Text(v), _)) => v.clone(),
+                        _ => {
+                            return Err(
+                                self.error(format!("Expected a value to compare '{}' with", word))
+                            )
+                        }
+                    };
+                    self.pos += 1;
+                    Ok(Expr::Compare(word, op, value))
+                }
+                _ => Ok(Expr::Text(word)),
+            },
+            Token::Text(text) => Ok(Expr::Text(text)),
+            Token::Field(field, value) => field_expr(&field, &value, offset),
+            Token::RParen => Err(ParseError {
+                message: "Unexpected ')'".to_string(),
+                offset,
+            }),
+            Token::Cmp(_) => Err(ParseError {
+                message: "Expected an attribute name before comparison".to_string(),
+                offset,
+            }),
+            Token::And | Token::Or => Err(ParseError {
+                message: "Expected a term before operator".to_string(),
+                offset,
+            }),
+        }
+    }
+}
+
+fn field_expr(field: &str, value: &str, offset: usize) -> Result<Expr, ParseError> {
+    match field {
+        "path" => Ok(Expr::Path(glob_to_regex(value))),
+        "task" => match value {
+            "open" => Ok(Expr::Task(TaskState::Open)),
+            "done" => Ok(Expr::Task(TaskState::Done)),
+            "cancelled" => Ok(Expr::Task(TaskState::Cancelled)),
+            _ => Err(ParseError {
+                message: format!(
+                    "Unknown task state '{}' (expected open, done or cancelled)",
+                    value
+                ),
+                offset,
+            }),
+        },
+        _ => Err(ParseError {
+            message: format!("Unknown field '{}'", field),
+            offset,
+        }),
+    }
+}
+
+/// Parse a query string into an expression.
+pub fn parse(input: &str) -> Result<Expr, ParseError> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        end: input.len(),
+    };
+    if parser.peek().is_none() {
+        return Err(parser.error("Query is empty"));
+    }
+    let expr = parser.parse_or()?;
+    if parser.peek().is_some() {
+        return Err(parser.error("Unexpected ')'"));
+    }
+    Ok(expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown::parse_list_items;
+
+    fn matching(query: &str, file_path: &str, content: &str) -> Vec<String> {
+        let expr = parse(query).unwrap();
+        parse_list_items(content)
+            .iter()
+            .filter(|node| {
+                expr.matches(&NodeContext {
+                    file_path,
+                    node,
+                    tags: &node.tags,
+                })
+            })
+            .map(|n| n.text.clone())
+            .collect()
+    }
+
+    #[test]
+    fn and_not_excludes_tag() {
+        let content = "- Use REST #decision\n- Use gRPC #decision #superseded\n- Misc";
+        assert_eq!(
+            matching("#decision AND NOT #superseded", "a.md", content),
+            vec!["Use REST #decision"]
+        );
+    }
+
+    #[test]
+    fn parentheses_and_path_glob() {
+        let content = "- Cache builds #ci\n- Add node #infra\n- Other #docs";
+        let query = "(#ci OR #infra) AND path:projects/*";
+        assert_eq!(matching(query, "projects/x.md", content).len(), 2);
+        assert!(matching(query, "projects/sub/x.md", content).is_empty());
+        assert_eq!(
+            matching("#ci path:projects/**", "projects/sub/x.md", content).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn text_terms_are_case_insensitive() {
+        let content = "- Rollback Plan #ops\n- rollout #ops";
+        assert_eq!(
+            matching("\"rollback plan\"", "a.md", content),
+            vec!["Rollback Plan #ops"]
+        );
+        assert_eq!(matching("#ops roll", "a.md", content).len(), 2);
+    }
+
+    #[test]
+    fn tag_term_matches_descendants() {
+        let content = "- A #project/alpha/api\n- B #project\n- C #projects";
+        assert_eq!(matching("#project", "a.md", content).len(), 2);
+        assert_eq!(
+            matching("#project/alpha", "a.md", content),
+            vec!["A #project/alpha/api"]
+        );
+        assert!(parse("#project/")
+            .unwrap_err()
+            .message
+            .contains("Invalid tag"));
+    }
+
+    #[test]
+    fn task_field_filters_by_state() {
+        let content = "- [ ] a #todo\n- [x] b #todo";
+        assert_eq!(
+            matching("#todo task:open", "a.md", content),
+            vec!["a #todo"]
+        );
+    }
+
+    #[test]
+    fn attribute_comparisons() {
+        let content = "- A #decision status:: accepted due:: 2026-11-01 cost:: 9\n\
+                       - B #decision status:: proposed due:: 2027-01-15 cost:: 10\n\
+                       - C #decision";
+        assert_eq!(matching("status = accepted", "a.md", content).len(), 1);
+        assert_eq!(matching("status=Accepted", "a.md", content).len(), 1);
+        assert_eq!(
+            matching("#decision status != accepted", "a.md", content).len(),
+            1
+        );
+        assert_eq!(matching("due < 2026-12-01", "a.md", content).len(), 1);
+        assert_eq!(matching("due >= 2026-11-01", "a.md", content).len(), 2);
+        // Numeric, not lexicographic: 9 < 10.
+        assert_eq!(
+            matching("cost > 9", "a.md", content),
+            vec!["B #decision status:: proposed due:: 2027-01-15 cost:: 10"]
+        );
+        assert!(parse("status =")
+            .unwrap_err()
+            .message
+            .contains("Expected a value"));
+        assert!(parse("= accepted")
+            .unwrap_err()
+            .message
+            .contains("attribute name"));
+    }
+
+    #[test]
+    fn compare_values_orders_numbers_and_text() {
+        assert_eq!(compare_values("9", "10"), Ordering::Less);
+        assert_eq!(
+            compare_values("2026-11-01", "2026-02-01"),
+            Ordering::Greater
+        );
+        assert_eq!(compare_values("Alice", "alice"), Ordering::Equal);
+    }
+
+    #[test]
+    fn invalid_syntax_reports_position() {
+        let err = parse("(#ci OR #infra").unwrap_err();
+        assert_eq!(err.message, "Expected ')'");
+        assert_eq!(err.offset, 14);
+
+        let err = parse("#ci AND").unwrap_err();
+        assert_eq!(err.message, "Expected a term");
+
+        let err = parse("OR #ci").unwrap_err();
+        assert_eq!(err.offset, 0);
+
+        assert!(parse("#ci)").unwrap_err().message.contains("')'"));
+        assert!(parse("owner:bob")
+            .unwrap_err()
+            .message
+            .contains("Unknown field"));
+        assert!(parse("\"open")
+            .unwrap_err()
+            .message
+            .contains("Unterminated"));
+        assert!(parse("#").unwrap_err().message.contains("Invalid tag"));
+        assert_eq!(parse("  ").unwrap_err().message, "Query is empty");
+    }
+}
@@ -0,0 +1,334 @@
+//! Vault-wide search and replace. A call without a selection is a dry run that lists
+//! every change per file; a call with a selection applies the chosen changes, writing
+//! each file once through `write_file_impl`. Matching uses the same rules as plain
+//! `search_full_text` (see `SearchOptions`).
+
+use crate::error::LedgerError;
+use serde::{Deserialize, Serialize};
+
+use super::file::{read_file_versioned_impl, same_version, write_file_impl, FileContent};
+use super::search::{build_matcher, utf16_offset, Matcher, SearchOptions};
+use super::vault::Vault;
+
+/// Changes to apply in one file, chosen from a dry-run preview.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReplaceSelection {
+    pub file_path: String,
+    /// `version` from the preview (or from `read_file`). The file is skipped if it
+    /// changed since.
+    pub version: String,
+    /// Change ids to apply; every change in the file if omitted.
+    #[serde(default)]
+    pub change_ids: Option<Vec<usize>>,
+}
+
+/// One replacement. Offsets are relative to the line, in bytes and UTF-16 code units.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplaceChange {
+    /// Index of the change in its file; stable while the file is unchanged.
+    pub id: usize,
+    /// 0-based line number.
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+    pub start_utf16: usize,
+    pub end_utf16: usize,
+    /// Text that will be replaced.
+    pub matched: String,
+    /// Text it will be replaced with (capture groups expanded).
+    pub replacement: String,
+    /// The line as it is now.
+    pub line_text: String,
+    /// The line with only this change applied.
+    pub preview: String,
+    /// Byte offset of the line in the file.
+    #[serde(skip)]
+    line_offset: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileReplace {
+    pub file_path: String,
+    /// Version of the file the changes were computed from, as `read_file` returns it.
+    pub version: String,
+    pub changes: Vec<ReplaceChange>,
+    /// True if the file was written (apply only).
+    pub applied: bool,
+    /// Why the file could not be read or written. `Conflict` if it changed since the
+    /// preview.
+    pub error: Option<LedgerError>,
+}
+
+/// Lines of `content` with their byte offsets, split like `str::lines`.
+fn lines_with_offsets(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    content.split_inclusive('\n').scan(0, |offset, raw| {
+        let start = *offset;
+        *offset += raw.len();
+        let line = raw.strip_suffix('\n').unwrap_or(raw);
+        Some((start, line.strip_suffix('\r').unwrap_or(line)))
+    })
+}
+
+/// Replacement text for the match at `line[start..end]`. In regex mode `$1`, `${name}`
+/// refer to capture groups; otherwise the replacement is literal.
+fn expand(
+    matcher: &Matcher,
+    options: SearchOptions,
+    line: &str,
+    start: usize,
+    replacement: &str,
+) -> String {
+    if let (Matcher::Regex(re), true) = (matcher, options.regex) {
+        if let Some(caps) = re.captures_at(line, start) {
+            if caps.get(0).is_some_and(|m| m.start() == start) {
+                let mut out = String::new();
+                caps.expand(replacement, &mut out);
+                return out;
+            }
+        }
+    }
+    replacement.to_string()
+}
+
+fn plan_changes(
+    content: &str,
+    matcher: &Matcher,
+    options: SearchOptions,
+    replacement: &str,
+) -> Vec<ReplaceChange> {
+    let mut changes = Vec::new();
+    for (line_number, (line_offset, line)) in lines_with_offsets(content).enumerate() {
+        for (start, end) in matcher.find(line, options.whole_word) {
+            let new_text = expand(matcher, options, line, start, replacement);
+            changes.push(ReplaceChange {
+                id: changes.len(),
+                line: line_number,
+                start,
+                end,
+                start_utf16: utf16_offset(line, start),
+                end_utf16: utf16_offset(line, end),
+                matched: line[start..end].to_string(),
+                preview: format!("{}{}{}", &line[..start], new_text, &line[end..]),
+                replacement: new_text,
+                line_text: line.to_string(),
+                line_offset,
+            });
+        }
+    }
+    changes
+}
+
+/// Apply `changes` (in file order, non-overlapping) to `content`.
+fn apply_changes(content: &str, changes: &[ReplaceChange]) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut copied = 0;
+    for c in changes {
+        let start = c.line_offset + c.start;
+        out.push_str(&content[copied..start]);
+        out.push_str(&c.replacement);
+        copied = c.line_offset + c.end;
+    }
+    out.push_str(&content[copied..]);
+    out
+}
+
+fn apply_selection(
+    root_path: &str,
+    sel: &ReplaceSelection,
+    matcher: &Matcher,
+    options: SearchOptions,
+    replacement: &str,
+) -> Result<(String, Vec<ReplaceChange>), LedgerError> {
+    let FileContent { content, version } = read_file_versioned_impl(root_path, &sel.file_path)?;
+    if !same_version(&version, &sel.version) {
+        return Err(LedgerError::Conflict {
+            path: sel.file_path.clone(),
+            version: Some(version),
+            content: Some(content),
+        });
+    }
+    let mut changes = plan_changes(&content, matcher, options, replacement);
+    if let Some(ids) = &sel.change_ids {
+        if let Some(bad) = ids.iter().find(|id| **id >= changes.len()) {
+            return Err(LedgerError::invalid_input(format!(
+                "Unknown change id: {}",
+                bad
+            )));
+        }
+        changes.retain(|c| ids.contains(&c.id));
+    }
+    if !changes.is_empty() {
+        write_file_impl(
+            root_path,
+            &sel.file_path,
+            &apply_changes(&content, &changes),
+        )?;
+    }
+    Ok((version, changes))
+}
+
+/// Core replace logic for testing. With `selection` None, returns a preview of every
+/// change in `paths` (files without matches are left out). With a selection, applies it
+/// and returns one entry per selected file; a failure in one file does not stop the
+/// others.
+pub fn replace_in_vault_impl(
+    root_path: &str,
+    paths: &[String],
+    query: &str,
+    replacement: &str,
+    options: SearchOptions,
+    selection: Option<&[ReplaceSelection]>,
+) -> Result<Vec<FileReplace>, LedgerError> {
+    if query.is_empty() {
+        return Err(LedgerError::invalid_input("Query must not be empty"));
+    }
+    let matcher = build_matcher(query, options)?;
+
+    let Some(selection) = selection else {
+        let mut files = Vec::new();
+        for path in paths {
+            let (version, changes, error) = match read_file_versioned_impl(root_path, path) {
+                Ok(FileContent { content, version }) => (
+                    version,
+                    plan_changes(&content, &matcher, options, replacement),
+                    None,
+                ),
+                Err(e) => (String::new(), Vec::new(), Some(e)),
+            };
+            if !changes.is_empty() || error.is_some() {
+                files.push(FileReplace {
+                    file_path: path.clone(),
+                    version,
+                    changes,
+                    applied: false,
+                    error,
+                });
+            }
+        }
+        return Ok(files);
+    };
+
+    let mut files = Vec::new();
+    for sel in selection {
+        let result = if paths.contains(&sel.file_path) {
+            apply_selection(root_path, sel, &matcher, options, replacement)
+        } else {
+            Err(LedgerError::NotFound {
+                path: sel.file_path.clone(),
+            })
+        };
+        files.push(match result {
+            Ok((version, changes)) => FileReplace {
+                file_path: sel.file_path.clone(),
+                version,
+                applied: !changes.is_empty(),
+                changes,
+                error: None,
+            },
+            Err(e) => FileReplace {
+                file_path: sel.file_path.clone(),
+                version: sel.version.clone(),
+                changes: Vec::new(),
+                applied: false,
+                error: Some(e),
+            },
+        });
+    }
+    Ok(files)
+}
+
+impl Vault {
+    /// Preview or apply a vault-wide replace (see `replace_in_vault_impl`). Only notes in
+    /// the vault are considered; `paths` narrows them further. Applied files are
+    /// re-indexed.
+    pub fn replace(
+        &mut self,
+        query: &str,
+        replacement: &str,
+        options: SearchOptions,
+        paths: Option<&[String]>,
+        selection: Option<&[ReplaceSelection]>,
+    ) -> Result<Vec<FileReplace>, LedgerError> {
+        let file_list: Vec<String> = match paths {
+            Some(p) => p
+                .iter()
+                .filter(|f| self.file_paths.contains(f))
+                .cloned()
+                .collect(),
+            None => self.file_paths.clone(),
+        };
+        let files = replace_in_vault_impl(
+            &self.root_path,
+            &file_list,
+            query,
+            replacement,
+            options,
+            selection,
+        )?;
+        for f in files.iter().filter(|f| f.applied) {
+            self.reindex_file(&f.file_path);
+        }
+        Ok(files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_and_apply_keep_line_endings() {
+        let content = "- Apollo launch\r\n- apollo notes\n\n- APOLLO\n";
+        let matcher = build_matcher("apollo", SearchOptions::default()).unwrap();
+        let changes = plan_changes(content, &matcher, SearchOptions::default(), "Artemis");
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[1].line, 1);
+        assert_eq!(changes[1].preview, "- Artemis notes");
+        assert_eq!(changes[2].line, 3);
+        assert_eq!(
+            apply_changes(content, &changes),
+            "- Artemis launch\r\n- Artemis notes\n\n- Artemis\n"
+        );
+        assert_eq!(
+            apply_changes(content, &changes[1..2]),
+            "- Apollo launch\r\n- Artemis notes\n\n- APOLLO\n"
+        );
+    }
+
+    #[test]
+    fn folded_matches_never_split_a_character() {
+        let content = "- groß s ﬁx";
+        let options = SearchOptions::default();
+        let changes = plan_changes(content, &build_matcher("S", options).unwrap(), options, "z");
+        assert_eq!(apply_changes(content, &changes), "- groß z ﬁx");
+        let changes = plan_changes(
+            content,
+            &build_matcher("fi", options).unwrap(),
+            options,
+            "F",
+        );
+        assert_eq!(apply_changes(content, &changes), "- groß s Fx");
+    }
+
+    #[test]
+    fn regex_capture_groups_are_expanded() {
+        let options = SearchOptions {
+            regex: true,
+            case_sensitive: true,
+            ..Default::default()
+        };
+        let matcher = build_matcher(r"PROJ-(\d+)", options).unwrap();
+        let changes = plan_changes("- PROJ-7 and PROJ-42", &matcher, options, "TEAM-$1");
+        let replaced: Vec<&str> = changes.iter().map(|c| c.replacement.as_str()).collect();
+        assert_eq!(replaced, vec!["TEAM-7", "TEAM-42"]);
+
+        // Literal mode keeps `$` as typed.
+        let literal = SearchOptions {
+            case_sensitive: true,
+            ..Default::default()
+        };
+        let matcher = build_matcher("PROJ", literal).unwrap();
+        let changes = plan_changes("- PROJ-7", &matcher, literal, "$1");
+        assert_eq!(changes[0].replacement, "$1");
+    }
+}
@@ -0,0 +1,510 @@
+//! Full-text search across vault files. File text comes from the vault index, so only
+//! files changed since the last call are read from disk. Results capped at MAX_RESULTS
+//! (100) so UI stays responsive.
+//!
+//! Plain search returns lines matching the query in file order, with every occurrence in
+//! the line; `SearchOptions` select case sensitivity, whole words and regex patterns.
+//! Case-insensitive literal search compares case-folded text (so `STRASSE` finds
+//! `Straße` and `istanbul` finds `İstanbul`) and maps matches back to the original line.
+//! Offsets are reported both as UTF-8 bytes and as UTF-16 code units for the editor.
+//! Fuzzy search scores every line with `fuzzy_matcher` (clangd-style: consecutive and
+//! word-start matches score higher) and returns the best lines across the whole vault.
+
+use crate::error::LedgerError;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::vault::Vault;
+
+const MAX_RESULTS: usize = 100;
+
+/// How plain search interprets the query. All off by default: case-insensitive literal
+/// text matched anywhere.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct SearchOptions {
+    pub case_sensitive: bool,
+    /// Only match where the neighbouring characters are not letters, digits or `_`.
+    pub whole_word: bool,
+    /// Treat the query as a regular expression (Rust `regex` syntax).
+    pub regex: bool,
+}
+
+/// One occurrence in a line: [start, end) as byte offsets and as UTF-16 code units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct MatchRange {
+    pub start: usize,
+    pub end: usize,
+    pub start_utf16: usize,
+    pub end_utf16: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchMatch {
+    pub file_path: String,
+    /// 0-based line number in the file.
+    pub line: usize,
+    #[serde(rename = "snippet_or_line")]
+    pub snippet_or_line: String,
+    pub start_offset: Option<usize>,
+    pub end_offset: Option<usize>,
+    pub start_offset_utf16: Option<usize>,
+    pub end_offset_utf16: Option<usize>,
+    /// Every occurrence in the line, in order. Empty for fuzzy search.
+    pub matches: Vec<MatchRange>,
+    /// Fuzzy score, higher is better. None for plain search.
+    pub score: Option<i64>,
+    /// Byte offsets in the line of each matched character. Fuzzy search only.
+    pub positions: Option<Vec<usize>>,
+    /// `positions` as UTF-16 code units. Fuzzy search only.
+    pub positions_utf16: Option<Vec<usize>>,
+}
+
+/// How plain search finds occurrences in a line. Shared with `replace_in_vault`.
+pub(crate) enum Matcher {
+    /// Case-insensitive literal text, compared after `fold`.
+    Folded(String),
+    /// Regex mode, or case-sensitive literal text.
+    Regex(Regex),
+}
+
+/// Case-fold one character for comparison. Lowercasing is not enough for German and
+/// Turkish: `ß` should match `ss`, and `İ`/`ı` should match a plain `i` (lowercasing
+/// `İ` gives `i` plus a combining dot). Latin ligatures such as `ﬁ` fold to their
+/// letters.
+fn fold_char(c: char, out: &mut String) {
+    match c {
+        'ß' | 'ẞ' => out.push_str("ss"),
+        'ﬀ' => out.push_str("ff"),
+        'ﬁ' => out.push_str("fi"),
+        'ﬂ' => out.push_str("fl"),
+        'ﬃ' => out.push_str("ffi"),
+        'ﬄ' => out.push_str("ffl"),
+        'ﬅ' | 'ﬆ' => out.push_str("st"),
+        'İ' | 'ı' => out.push('i'),
+        'ς' => out.push('σ'),
+        _ => out.extend(c.to_lowercase()),
+    }
+}
+
+fn fold(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        fold_char(c, &mut out);
+    }
+    out
+}
+
+/// All occurrences of `needle` (already folded) in `line`, as original byte ranges. A
+/// match must cover whole original characters: `s` does not match inside `ß`, since
+/// replacing it would turn `groß` into `groz`.
+fn find_folded(line: &str, needle: &str) -> Vec<(usize, usize)> {
+    let mut folded = String::with_capacity(line.len());
+    // For each folded byte: the byte range of the original character it came from.
+    let mut origin: Vec<(usize, usize)> = Vec::with_capacity(line.len());
+    // Folded offsets where one original character's folding starts.
+    let mut boundary: Vec<bool> = Vec::with_capacity(line.len() + 1);
+    for (start, c) in line.char_indices() {
+        let before = folded.len();
+        fold_char(c, &mut folded);
+        let range = (start, start + c.len_utf8());
+        origin.extend(std::iter::repeat(range).take(folded.len() - before));
+        boundary.push(true);
+        boundary.extend(std::iter::repeat(false).take(folded.len() - before - 1));
+    }
+    boundary.push(true);
+    let mut out: Vec<(usize, usize)> = Vec::new();
+    let mut searched = 0;
+    while let Some(found) = folded[searched..].find(needle) {
+        let pos = searched + found;
+        let end = pos + needle.len();
+        if boundary[pos] && boundary[end] {
+            out.push((origin[pos].0, origin[end - 1].1));
+            searched = end;
+        } else {
+            searched = pos + folded[pos..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    out
+}
+
+/// Compile the query for plain search. Literal queries are escaped, so only regex mode
+/// can fail.
+pub(crate) fn build_matcher(query: &str, options: SearchOptions) -> Result<Matcher, LedgerError> {
+    if !options.regex && !options.case_sensitive {
+        return Ok(Matcher::Folded(fold(query)));
+    }
+    let pattern = if options.regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .build()
+        .map(Matcher::Regex)
+        .map_err(|e| LedgerError::Parse {
+            message: format!("Invalid regex: {}", e),
+            offset: None,
+        })
+}
+
+/// UTF-16 length of `line[..byte]`. `byte` must be a char boundary.
+pub(crate) fn utf16_offset(line: &str, byte: usize) -> usize {
+    line[..byte].chars().map(char::len_utf16).sum()
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// True if `line[start..end]` is not directly preceded or followed by a word character.
+fn is_whole_word(line: &str, start: usize, end: usize) -> bool {
+    let before = line[..start].chars().next_back();
+    let after = line[end..].chars().next();
+    !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+}
+
+impl Matcher {
+    /// Every non-empty occurrence in `line` as byte ranges, in order.
+    pub(crate) fn find(&self, line: &str, whole_word: bool) -> Vec<(usize, usize)> {
+        let found = match self {
+            Matcher::Folded(needle) => find_folded(line, needle),
+            Matcher::Regex(re) => re
+                .find_iter(line)
+                .filter(|m| !m.is_empty())
+                .map(|m| (m.start(), m.end()))
+                .collect(),
+        };
+        found
+            .into_iter()
+            .filter(|&(start, end)| !whole_word || is_whole_word(line, start, end))
+            .collect()
+    }
+}
+
+/// Plain match: every non-empty occurrence of `matcher` in `line`.
+fn plain_match(
+    file_path: &str,
+    line_number: usize,
+    line: &str,
+    matcher: &Matcher,
+    options: SearchOptions,
+) -> Option<SearchMatch> {
+    let matches: Vec<MatchRange> = matcher
+        .find(line, options.whole_word)
+        .into_iter()
+        .map(|(start, end)| MatchRange {
+            start,
+            end,
+            start_utf16: utf16_offset(line, start),
+            end_utf16: utf16_offset(line, end),
+        })
+        .collect();
+    let first = *matches.first()?;
+    Some(SearchMatch {
+        file_path: file_path.to_string(),
+        line: line_number,
+        snippet_or_line: line.to_string(),
+        start_offset: Some(first.start),
+        end_offset: Some(first.end),
+        start_offset_utf16: Some(first.start_utf16),
+        end_offset_utf16: Some(first.end_utf16),
+        matches,
+        score: None,
+        positions: None,
+        positions_utf16: None,
+    })
+}
+
+/// Fuzzy match: the query's characters appear in order in `line`. Offsets span from the
+/// first to the last matched character.
+fn fuzzy_match(
+    file_path: &str,
+    line_number: usize,
+    line: &str,
+    query: &str,
+) -> Option<SearchMatch> {
+    let (score, char_indices) = fuzzy_matcher::fuzzy_indices(line, query)?;
+    // fuzzy_matcher reports char indices; convert them to byte offsets.
+    let mut positions = Vec::with_capacity(char_indices.len());
+    let mut wanted = char_indices.iter().peekable();
+    for (i, (byte, _)) in line.char_indices().enumerate() {
+        if wanted.peek() == Some(&&i) {
+            positions.push(byte);
+            wanted.next();
+        }
+    }
+    let start = positions.first().copied();
+    let end = positions
+        .last()
+        .map(|&p| p + line[p..].chars().next().map_or(0, char::len_utf8));
+    let positions_utf16 = positions.iter().map(|&p| utf16_offset(line, p)).collect();
+    Some(SearchMatch {
+        file_path: file_path.to_string(),
+        line: line_number,
+        snippet_or_line: line.to_string(),
+        start_offset: start,
+        end_offset: end,
+        start_offset_utf16: start.map(|p| utf16_offset(line, p)),
+        end_offset_utf16: end.map(|p| utf16_offset(line, p)),
+        matches: Vec::new(),
+        score: Some(score),
+        positions: Some(positions),
+        positions_utf16: Some(positions_utf16),
+    })
+}
+
+/// Search `files` (path, content) in order. Plain results stop at MAX_RESULTS; fuzzy
+/// results are ranked by score (ties keep file order) and then truncated. Errors if the
+/// query is an invalid regex or combines fuzzy with regex or whole-word.
+fn search_files<'a>(
+    files: impl IntoIterator<Item = (&'a str, &'a str)>,
+    query: &str,
+    fuzzy: bool,
+    options: SearchOptions,
+) -> Result<Vec<SearchMatch>, LedgerError> {
+    if fuzzy && (options.regex || options.whole_word) {
+        return Err(LedgerError::invalid_input(
+            "Fuzzy search cannot be combined with regex or whole-word",
+        ));
+    }
+    let matcher = if fuzzy {
+        None
+    } else {
+        Some(build_matcher(query, options)?)
+    };
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut results = Vec::new();
+    for (file_path, content) in files {
+        for (line_number, line) in content.lines().enumerate() {
+            let m = match &matcher {
+                Some(matcher) => plain_match(file_path, line_number, line, matcher, options),
+                None => fuzzy_match(file_path, line_number, line, query),
+            };
+            if let Some(m) = m {
+                results.push(m);
+                if !fuzzy && results.len() >= MAX_RESULTS {
+                    return Ok(results);
+                }
+            }
+        }
+    }
+    if fuzzy {
+        results.sort_by_key(|m| std::cmp::Reverse(m.score));
+        results.truncate(MAX_RESULTS);
+    }
+    Ok(results)
+}
+
+impl Vault {
+    /// Search the notes in `paths` (or the whole vault). See the module docs.
+    pub fn search(
+        &mut self,
+        query: &str,
+        paths: Option<&[String]>,
+        fuzzy: bool,
+        options: SearchOptions,
+    ) -> Result<Vec<SearchMatch>, LedgerError> {
+        let file_list = self.file_list(paths);
+        self.refresh_index(&file_list);
+        self.index
+            .load_contents(Path::new(&self.root_path), &file_list);
+        let files = file_list.iter().filter_map(|rel| {
+            let content = self.index.get(rel)?.content.as_deref()?;
+            Some((rel.as_str(), content))
+        });
+        search_files(files, query, fuzzy, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_search_keeps_file_order() {
+        let files = [
+            ("a.md", "- Hello world\n- nothing"),
+            ("b.md", "- hello again"),
+        ];
+        let results = search_files(files, "HELLO", false, SearchOptions::default()).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].file_path, "a.md");
+        assert_eq!(
+            (results[0].start_offset, results[0].end_offset),
+            (Some(2), Some(7))
+        );
+        assert!(results[0].score.is_none());
+    }
+
+    #[test]
+    fn fuzzy_search_ranks_across_files() {
+        let files = [
+            ("a.md", "- deploy checklist for the release"),
+            ("b.md", "- decisions\n- deploy"),
+            ("c.md", "- unrelated"),
+        ];
+        let results = search_files(files, "dply", true, SearchOptions::default()).unwrap();
+        assert_eq!(results.len(), 2);
+        // The short, tight match in b.md outranks the earlier file.
+        assert_eq!(results[0].snippet_or_line, "- deploy");
+        assert!(results[0].score > results[1].score);
+        assert_eq!(results[0].positions, Some(vec![2, 4, 5, 7]));
+        assert_eq!(
+            (results[0].start_offset, results[0].end_offset),
+            (Some(2), Some(8))
+        );
+    }
+
+    #[test]
+    fn fuzzy_positions_are_byte_offsets() {
+        let results = search_files(
+            [("a.md", "- Straße offen")],
+            "so",
+            true,
+            SearchOptions::default(),
+        )
+        .unwrap();
+        let line = &results[0].snippet_or_line;
+        let positions = results[0].positions.clone().unwrap();
+        let picked: String = positions
+            .iter()
+            .map(|&p| line[p..].chars().next().unwrap())
+            .collect();
+        assert_eq!(picked.to_lowercase(), "so");
+        assert!(
+            search_files([("a.md", "- x")], "", true, SearchOptions::default())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    fn ranges(results: &[SearchMatch]) -> Vec<(usize, usize)> {
+        results
+            .iter()
+            .flat_map(|m| m.matches.iter().map(|r| (r.start, r.end)))
+            .collect()
+    }
+
+    #[test]
+    fn plain_search_reports_every_match_in_line() {
+        let files = [("a.md", "- ABC-12 blocks abc-12 and ABC-123")];
+        let results = search_files(files, "abc-12", false, SearchOptions::default()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(ranges(&results), vec![(2, 8), (16, 22), (27, 33)]);
+
+        let exact = SearchOptions {
+            case_sensitive: true,
+            whole_word: true,
+            ..Default::default()
+        };
+        let results = search_files(files, "ABC-12", false, exact).unwrap();
+        assert_eq!(ranges(&results), vec![(2, 8)]);
+    }
+
+    #[test]
+    fn regex_search_and_validation() {
+        let options = SearchOptions {
+            regex: true,
+            ..Default::default()
+        };
+        let files = [("a.md", "- PROJ-1 and proj-22\n- none")];
+        let results = search_files(files, r"PROJ-\d+", false, options).unwrap();
+        assert_eq!(ranges(&results), vec![(2, 8), (13, 20)]);
+
+        // Patterns that match the empty string do not report empty matches.
+        assert!(search_files(files, "x*", false, options)
+            .unwrap()
+            .is_empty());
+
+        let err = search_files(files, "(unclosed", false, options).unwrap_err();
+        assert_eq!(err.code(), "Parse");
+        assert!(err.to_string().starts_with("Invalid regex"), "{}", err);
+        let err = search_files(files, "a", true, options).unwrap_err();
+        assert_eq!(err.code(), "InvalidInput");
+    }
+
+    #[test]
+    fn german_case_folding_maps_to_original_offsets() {
+        let files = [("de.md", "- Die Straße ist groß\n- STRASSE gesperrt")];
+        let results = search_files(files, "strasse", false, SearchOptions::default()).unwrap();
+        assert_eq!(results.len(), 2);
+        let m = &results[0];
+        assert_eq!(m.line, 0);
+        // "Straße" is 7 bytes (ß is 2) and 6 UTF-16 units.
+        assert_eq!(
+            &m.snippet_or_line[m.start_offset.unwrap()..m.end_offset.unwrap()],
+            "Straße"
+        );
+        assert_eq!(m.matches[0].start_utf16, 6);
+        assert_eq!(m.matches[0].end_utf16, 12);
+        assert_eq!(results[1].line, 1);
+
+        // The query is folded too; a lone "s" never splits ß.
+        let results = search_files(files, "GROß", false, SearchOptions::default()).unwrap();
+        assert_eq!(ranges(&results), vec![(18, 23)]);
+        let results = search_files([("a.md", "ß")], "s", false, SearchOptions::default()).unwrap();
+        assert!(results.is_empty());
+        let results =
+            search_files([("a.md", "groß s")], "s", false, SearchOptions::default()).unwrap();
+        assert_eq!(ranges(&results), vec![(6, 7)]);
+    }
+
+    #[test]
+    fn ligatures_match_only_whole() {
+        // "ﬁ" is 3 bytes and folds to "fi".
+        let files = [("a.md", "- ﬁle office")];
+        let results = search_files(files, "FILE", false, SearchOptions::default()).unwrap();
+        assert_eq!(ranges(&results), vec![(2, 7)]);
+        // Single letters only match outside the ligature.
+        let results = search_files(files, "f", false, SearchOptions::default()).unwrap();
+        assert_eq!(ranges(&results), vec![(9, 10), (10, 11)]);
+        let results = search_files(files, "i", false, SearchOptions::default()).unwrap();
+        assert_eq!(ranges(&results), vec![(11, 12)]);
+    }
+
+    #[test]
+    fn turkish_dotted_and_dotless_i() {
+        let files = [("tr.md", "- İstanbul'da ılık bir gün\n- ISTANBUL")];
+        let results = search_files(files, "istanbul", false, SearchOptions::default()).unwrap();
+        assert_eq!(results.len(), 2);
+        let m = &results[0];
+        // İ is 2 bytes but 1 UTF-16 unit.
+        assert_eq!((m.matches[0].start, m.matches[0].end), (2, 11));
+        assert_eq!((m.matches[0].start_utf16, m.matches[0].end_utf16), (2, 10));
+
+        let results = search_files(files, "ILIK", false, SearchOptions::default()).unwrap();
+        let m = &results[0];
+        assert_eq!(
+            &m.snippet_or_line[m.matches[0].start..m.matches[0].end],
+            "ılık"
+        );
+        assert_eq!(m.matches[0].start_utf16, 14);
+    }
+
+    #[test]
+    fn utf16_offsets_count_surrogate_pairs() {
+        let files = [("a.md", "- 🚀 Größe 🚀 größe")];
+        let results = search_files(files, "größe", false, SearchOptions::default()).unwrap();
+        let utf16: Vec<(usize, usize)> = results[0]
+            .matches
+            .iter()
+            .map(|r| (r.start_utf16, r.end_utf16))
+            .collect();
+        assert_eq!(utf16, vec![(5, 10), (14, 19)]);
+
+        let options = SearchOptions {
+            case_sensitive: true,
+            ..Default::default()
+        };
+        let results = search_files(files, "größe", false, options).unwrap();
+        assert_eq!(results[0].start_offset_utf16, Some(14));
+
+        let results = search_files(files, "gre", true, SearchOptions::default()).unwrap();
+        assert_eq!(results[0].positions_utf16.as_deref(), Some(&[5, 6, 9][..]));
+    }
+}
@@ -0,0 +1,247 @@
+//! Vault-wide tag catalogue and maintenance. Tags only exist inline in list item text,
+//! so listing them means scanning every file and renaming one means rewriting them.
+
+use crate::error::LedgerError;
+use crate::markdown::{self, TreeNode};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::file::{read_file_impl, write_file_impl};
+use super::vault::Vault;
+
+/// Co-occurring tags returned per tag.
+const MAX_CO_OCCURRING: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TagCount {
+    pub name: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagInfo {
+    pub name: String,
+    /// List items carrying the tag.
+    pub node_count: usize,
+    pub file_count: usize,
+    /// Oldest and newest modification time (Unix ms) of files using the tag.
+    pub first_seen_ms: u64,
+    pub last_seen_ms: u64,
+    /// Tags most often on the same list item, most frequent first.
+    pub co_occurring: Vec<TagCount>,
+}
+
+#[derive(Default)]
+struct TagStats {
+    node_count: usize,
+    files: BTreeSet<String>,
+    first_seen_ms: u64,
+    last_seen_ms: u64,
+    co: BTreeMap<String, usize>,
+}
+
+/// Build the catalogue from (path, mtime in ns, nodes) per file. Sorted by tag name.
+fn tag_catalogue<'a>(
+    files: impl IntoIterator<Item = (&'a str, u64, &'a [TreeNode])>,
+) -> Vec<TagInfo> {
+    let mut stats: BTreeMap<String, TagStats> = BTreeMap::new();
+    for (path, mtime_ns, nodes) in files {
+        let mtime_ms = mtime_ns / 1_000_000;
+        for node in nodes {
+            let tags: BTreeSet<&String> = node.tags.iter().collect();
+            for tag in &tags {
+                let s = stats.entry(tag.to_string()).or_default();
+                if s.files.is_empty() {
+                    s.first_seen_ms = mtime_ms;
+                    s.last_seen_ms = mtime_ms;
+                }
+                s.node_count += 1;
+                if s.files.insert(path.to_string()) {
+                    s.first_seen_ms = s.first_seen_ms.min(mtime_ms);
+                    s.last_seen_ms = s.last_seen_ms.max(mtime_ms);
+                }
+                for other in tags.iter().filter(|o| *o != tag) {
+                    *s.co.entry(other.to_string()).or_default() += 1;
+                }
+            }
+        }
+    }
+    stats
+        .into_iter()
+        .map(|(name, s)| {
+            let mut co_occurring: Vec<TagCount> =
+                s.co.into_iter()
+                    .map(|(name, count)| TagCount { name, count })
+                    .collect();
+            // Stable sort keeps ties in name order.
+            co_occurring.sort_by_key(|t| std::cmp::Reverse(t.count));
+            co_occurring.truncate(MAX_CO_OCCURRING);
+            TagInfo {
+                name,
+                node_count: s.node_count,
+                file_count: s.files.len(),
+                first_seen_ms: s.first_seen_ms,
+                last_seen_ms: s.last_seen_ms,
+                co_occurring,
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagRenameFile {
+    pub file_path: String,
+    /// List items whose text changed.
+    pub nodes_changed: usize,
+    /// Tag occurrences renamed, or removed as duplicates after a merge.
+    pub occurrences: usize,
+    /// Why the file could not be read or written. The file is left unchanged.
+    pub error: Option<LedgerError>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagRenameReport {
+    pub dry_run: bool,
+    /// Files (and list items in them) changed, not counting files with an error.
+    pub files_changed: usize,
+    pub nodes_changed: usize,
+    pub files: Vec<TagRenameFile>,
+}
+
+/// Core rename logic for testing. Renames `from` to `to` in every file in `paths`
+/// (and `from/...` to `to/...` with `include_children`). If `to` is already in use the
+/// two tags are merged. With `dry_run`, reports what would change without writing.
+/// A file that cannot be read or written is reported with its error and does not stop
+/// the others.
+pub fn rename_tag_impl(
+    root_path: &str,
+    paths: &[String],
+    from: &str,
+    to: &str,
+    include_children: bool,
+    dry_run: bool,
+) -> Result<TagRenameReport, LedgerError> {
+    let from = from.trim_start_matches('#');
+    let to = to.trim_start_matches('#');
+    for name in [from, to] {
+        if !markdown::is_valid_tag(name) {
+            return Err(LedgerError::invalid_input(format!(
+                "Invalid tag name: {}",
+                name
+            )));
+        }
+    }
+    if from == to {
+        return Err(LedgerError::invalid_input("Tags must differ"));
+    }
+
+    let mut files = Vec::new();
+    for path in paths {
+        let content = match read_file_impl(root_path, path) {
+            Ok(content) => content,
+            Err(e) => {
+                files.push(TagRenameFile {
+                    file_path: path.clone(),
+                    nodes_changed: 0,
+                    occurrences: 0,
+                    error: Some(e),
+                });
+                continue;
+            }
+        };
+        let nodes = markdown::parse_list_items(&content);
+        let Some(rewrite) = markdown::rename_tag(&content, &nodes, from, to, include_children)
+        else {
+            continue;
+        };
+        let error = if dry_run {
+            None
+        } else {
+            write_file_impl(root_path, path, &rewrite.content).err()
+        };
+        files.push(TagRenameFile {
+            file_path: path.clone(),
+            nodes_changed: rewrite.nodes,
+            occurrences: rewrite.occurrences,
+            error,
+        });
+    }
+    let changed = files.iter().filter(|f| f.error.is_none());
+    Ok(TagRenameReport {
+        dry_run,
+        files_changed: changed.clone().count(),
+        nodes_changed: changed.map(|f| f.nodes_changed).sum(),
+        files,
+    })
+}
+
+impl Vault {
+    /// Every tag in the vault with usage counts, by name.
+    pub fn list_tags(&mut self) -> Vec<TagInfo> {
+        let file_list = self.file_paths.clone();
+        self.refresh_index(&file_list);
+        let files = file_list.iter().filter_map(|rel| {
+            self.index
+                .get(rel)
+                .map(|e| (rel.as_str(), e.mtime_ns, e.nodes.as_slice()))
+        });
+        tag_catalogue(files)
+    }
+
+    /// Rename or merge a tag across the vault (see `rename_tag_impl`).
+    pub fn rename_tag(
+        &mut self,
+        from: &str,
+        to: &str,
+        include_children: bool,
+        dry_run: bool,
+    ) -> Result<TagRenameReport, LedgerError> {
+        let report = rename_tag_impl(
+            &self.root_path,
+            &self.file_paths,
+            from,
+            to,
+            include_children,
+            dry_run,
+        )?;
+        if !dry_run {
+            for f in report.files.iter().filter(|f| f.error.is_none()) {
+                self.reindex_file(&f.file_path);
+            }
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalogue_counts_and_co_occurrence() {
+        let a = markdown::parse_list_items("- One #ci #build\n- Two #ci #ci\n- Three #decison");
+        let b = markdown::parse_list_items("- Four #ci #ops\n- Five #build #ci");
+        let tags = tag_catalogue([("a.md", 5_000_000_000, &a[..]), ("b.md", 2_000_000, &b[..])]);
+
+        let names: Vec<&str> = tags.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["build", "ci", "decison", "ops"]);
+        let ci = &tags[1];
+        assert_eq!((ci.node_count, ci.file_count), (4, 2));
+        assert_eq!((ci.first_seen_ms, ci.last_seen_ms), (2, 5_000));
+        assert_eq!(
+            ci.co_occurring,
+            vec![
+                TagCount {
+                    name: "build".into(),
+                    count: 2
+                },
+                TagCount {
+                    name: "ops".into(),
+                    count: 1
+                },
+            ]
+        );
+        assert_eq!(tags[2].file_count, 1);
+        assert!(tags[2].co_occurring.is_empty());
+    }
+}
@@ -0,0 +1,277 @@
+//! Vault-local trash. Deleted files and folders are moved to `.ledger/trash/<id>/`
+//! next to a `meta.json` recording where they came from and when, so they can be
+//! restored. Entries older than the `trash_retention_days` session setting are purged
+//! when the vault is opened.
+
+use crate::error::LedgerError;
+use crate::index::LEDGER_DIR;
+use crate::vault_path::VaultPath;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::vault::{collect_md_paths, Vault};
+
+const TRASH_DIR: &str = "trash";
+const META_FILENAME: &str = "meta.json";
+const MS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub id: String,
+    /// Vault-relative path the item was deleted from.
+    pub original_path: String,
+    /// Deletion time (Unix ms).
+    pub deleted_ms: u64,
+    pub is_folder: bool,
+}
+
+/// What to do when restoring onto a path that exists again.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreConflict {
+    /// Leave both untouched and return an error.
+    #[default]
+    Fail,
+    /// Restore next to the existing item as `name (restored).md`.
+    KeepBoth,
+}
+
+fn trash_dir(root: &Path) -> PathBuf {
+    root.join(LEDGER_DIR).join(TRASH_DIR)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+/// The trashed item inside an entry directory (named like the original).
+fn item_path(root: &Path, entry: &TrashEntry) -> PathBuf {
+    let name = Path::new(&entry.original_path)
+        .file_name()
+        .unwrap_or_default();
+    trash_dir(root).join(&entry.id).join(name)
+}
+
+/// Move `path` into the trash. A symlink is moved itself, not its target.
+pub(crate) fn move_to_trash(path: &VaultPath) -> Result<TrashEntry, LedgerError> {
+    let root = path.root();
+    let deleted_ms = now_ms();
+    let dir = trash_dir(root);
+    let mut id = deleted_ms.to_string();
+    let mut n = 1;
+    while dir.join(&id).exists() {
+        id = format!("{}-{}", deleted_ms, n);
+        n += 1;
+    }
+    let entry = TrashEntry {
+        id,
+        original_path: path.to_string(),
+        deleted_ms,
+        is_folder: path.full().is_dir(),
+    };
+    let entry_dir = dir.join(&entry.id);
+    let trash_error = |e| LedgerError::io(&entry_dir.to_string_lossy(), e);
+    std::fs::create_dir_all(&entry_dir).map_err(trash_error)?;
+    let meta = serde_json::to_string_pretty(&entry)?;
+    std::fs::write(entry_dir.join(META_FILENAME), meta).map_err(trash_error)?;
+    if let Err(e) = std::fs::rename(path.entry(), item_path(root, &entry)) {
+        let _ = std::fs::remove_dir_all(&entry_dir);
+        return Err(LedgerError::io(path.as_str(), e));
+    }
+    Ok(entry)
+}
+
+/// Core trash listing for testing. Newest first; entries without readable metadata
+/// are skipped.
+pub fn list_trash_impl(root_path: &str) -> Result<Vec<TrashEntry>, LedgerError> {
+    let dir = trash_dir(Path::new(root_path));
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut entries: Vec<TrashEntry> = std::fs::read_dir(&dir)
+        .map_err(|e| LedgerError::io(&dir.to_string_lossy(), e))?
+        .filter_map(|e| {
+            let meta = std::fs::read_to_string(e.ok()?.path().join(META_FILENAME)).ok()?;
+            serde_json::from_str(&meta).ok()
+        })
+        .collect();
+    entries.sort_by(|a, b| b.deleted_ms.cmp(&a.deleted_ms).then(b.id.cmp(&a.id)));
+    Ok(entries)
+}
+
+fn find_entry(root_path: &str, id: &str) -> Result<TrashEntry, LedgerError> {
+    list_trash_impl(root_path)?
+        .into_iter()
+        .find(|e| e.id == id)
+        .ok_or_else(|| LedgerError::TrashEntryNotFound { id: id.to_string() })
+}
+
+/// `path` with ` (restored)` (then ` (restored 2)`, ...) added before the extension.
+fn restored_name(path: &str, n: usize) -> String {
+    let p = Path::new(path);
+    let stem = p.file_stem().unwrap_or_default().to_string_lossy();
+    let suffix = if n == 1 {
+        " (restored)".to_string()
+    } else {
+        format!(" (restored {})", n)
+    };
+    let name = match p.extension() {
+        Some(ext) => format!("{}{}.{}", stem, suffix, ext.to_string_lossy()),
+        None => format!("{}{}", stem, suffix),
+    };
+    match p.parent().filter(|d| !d.as_os_str().is_empty()) {
+        Some(dir) => dir.join(name).to_string_lossy().into_owned(),
+        None => name,
+    }
+}
+
+/// Core restore logic for testing. Moves the entry back to its original path (creating
+/// folders as needed) and returns the vault path it was restored to.
+pub fn restore_from_trash_impl(
+    root_path: &str,
+    id: &str,
+    on_conflict: RestoreConflict,
+) -> Result<String, LedgerError> {
+    let root = Path::new(root_path);
+    let entry = find_entry(root_path, id)?;
+    // `meta.json` is just a file in the vault; check its path like any other.
+    let mut target = VaultPath::for_create(root, &entry.original_path)?;
+    let mut n = 1;
+    while target.exists() {
+        if on_conflict == RestoreConflict::Fail {
+            return Err(LedgerError::AlreadyExists {
+                path: entry.original_path,
+            });
+        }
+        target = VaultPath::for_create(root, &restored_name(&entry.original_path, n))?;
+        n += 1;
+    }
+    target.create_parents()?;
+    std::fs::rename(item_path(root, &entry), target.full())
+        .map_err(|e| LedgerError::io(target.as_str(), e))?;
+    let _ = std::fs::remove_dir_all(trash_dir(root).join(&entry.id));
+    Ok(target.to_string())
+}
+
+/// Core purge logic for testing. Permanently deletes the entries in `ids` (or every
+/// entry if None) that are older than `older_than_days` (if set). Returns the purged ids.
+pub fn purge_trash_impl(
+    root_path: &str,
+    ids: Option<&[String]>,
+    older_than_days: Option<u64>,
+) -> Result<Vec<String>, LedgerError> {
+    let cutoff = older_than_days.map(|d| now_ms().saturating_sub(d * MS_PER_DAY));
+    let dir = trash_dir(Path::new(root_path));
+    let mut purged = Vec::new();
+    for entry in list_trash_impl(root_path)? {
+        if ids.is_some_and(|ids| !ids.contains(&entry.id)) {
+            continue;
+        }
+        if cutoff.is_some_and(|c| entry.deleted_ms > c) {
+            continue;
+        }
+        let entry_dir = dir.join(&entry.id);
+        std::fs::remove_dir_all(&entry_dir)
+            .map_err(|e| LedgerError::io(&entry_dir.to_string_lossy(), e))?;
+        purged.push(entry.id);
+    }
+    Ok(purged)
+}
+
+impl Vault {
+    pub fn list_trash(&self) -> Result<Vec<TrashEntry>, LedgerError> {
+        list_trash_impl(&self.root_path)
+    }
+
+    /// Restore a trash entry and add its notes back to the vault. Returns the restored
+    /// path.
+    pub fn restore_from_trash(
+        &mut self,
+        id: &str,
+        on_conflict: RestoreConflict,
+    ) -> Result<String, LedgerError> {
+        let restored = restore_from_trash_impl(&self.root_path, id, on_conflict)?;
+        let full = self.root().join(&restored);
+        let mut notes = Vec::new();
+        if full.is_dir() {
+            collect_md_paths(&full, self.root(), &mut notes)
+                .map_err(|e| LedgerError::io(&restored, e))?;
+        } else if restored.ends_with(".md") {
+            notes.push(restored.clone());
+        }
+        for p in notes {
+            self.add_file(&p);
+        }
+        Ok(restored)
+    }
+
+    pub fn purge_trash(
+        &self,
+        ids: Option<&[String]>,
+        older_than_days: Option<u64>,
+    ) -> Result<Vec<String>, LedgerError> {
+        purge_trash_impl(&self.root_path, ids, older_than_days)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn trash_restore_with_conflict_and_purge() {
+        let root = std::env::temp_dir().join("ledger_test_trash");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("notes")).unwrap();
+        fs::write(root.join("notes/a.md"), "- one").unwrap();
+        let root_path = root.to_str().unwrap();
+
+        let entry = move_to_trash(&VaultPath::new(&root, "notes/a.md").unwrap()).unwrap();
+        assert!(!root.join("notes/a.md").exists());
+        let listed = list_trash_impl(root_path).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].original_path, "notes/a.md");
+        assert!(!listed[0].is_folder);
+
+        // A new file took the old path: fail by default, or keep both.
+        fs::write(root.join("notes/a.md"), "- new").unwrap();
+        let err = restore_from_trash_impl(root_path, &entry.id, RestoreConflict::Fail);
+        assert_eq!(
+            err.unwrap_err(),
+            LedgerError::AlreadyExists {
+                path: "notes/a.md".into()
+            }
+        );
+        let restored =
+            restore_from_trash_impl(root_path, &entry.id, RestoreConflict::KeepBoth).unwrap();
+        assert_eq!(
+            restored,
+            Path::new("notes").join("a (restored).md").to_str().unwrap()
+        );
+        assert_eq!(fs::read_to_string(root.join(&restored)).unwrap(), "- one");
+        assert!(list_trash_impl(root_path).unwrap().is_empty());
+
+        // Folders go to the trash whole; purge respects age.
+        let folder = move_to_trash(&VaultPath::new(&root, "notes").unwrap()).unwrap();
+        assert!(folder.is_folder);
+        assert!(purge_trash_impl(root_path, None, Some(1))
+            .unwrap()
+            .is_empty());
+        let purged = purge_trash_impl(root_path, None, None).unwrap();
+        assert_eq!(purged, vec![folder.id]);
+        assert!(list_trash_impl(root_path).unwrap().is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn restored_names_keep_extension_and_folder() {
+        assert_eq!(restored_name("a.md", 1), "a (restored).md");
+        assert_eq!(restored_name("a.md", 2), "a (restored 2).md");
+        assert_eq!(restored_name("projects", 1), "projects (restored)");
+    }
+}
@@ -0,0 +1,184 @@
+use crate::error::LedgerError;
+use crate::index::{VaultIndex, LEDGER_DIR};
+use serde::Serialize;
+use std::path::Path;
+
+/// An open vault: its root folder, the notes in it, and the index of their content.
+/// Operations that change files (in the other modules' `impl Vault` blocks) keep
+/// `file_paths` and the index in sync.
+#[derive(Debug)]
+pub struct Vault {
+    /// Canonical path of the vault folder.
+    pub root_path: String,
+    /// Vault-relative paths of every note, sorted.
+    pub file_paths: Vec<String>,
+    pub index: VaultIndex,
+    /// False for `open_read_only`: the index is kept in memory and never saved.
+    persist_index: bool,
+}
+
+impl Vault {
+    /// Open the folder at `path`: list its notes and bring the stored index up to date.
+    pub fn open(path: &str) -> Result<Vault, LedgerError> {
+        Self::open_with(path, true)
+    }
+
+    /// Like `open`, but nothing is written to the vault: the index is brought up to date
+    /// in memory only. For one-off readers such as the CLI's `query` and `tags`.
+    pub fn open_read_only(path: &str) -> Result<Vault, LedgerError> {
+        Self::open_with(path, false)
+    }
+
+    fn open_with(path: &str, persist_index: bool) -> Result<Vault, LedgerError> {
+        let result = open_vault_impl(path)?;
+        let mut index = VaultIndex::load(Path::new(&result.root_path));
+        index.retain_paths(&result.file_paths);
+        let mut vault = Vault {
+            root_path: result.root_path,
+            file_paths: result.file_paths,
+            index,
+            persist_index,
+        };
+        let paths = vault.file_paths.clone();
+        vault.refresh_index(&paths);
+        Ok(vault)
+    }
+
+    pub fn root(&self) -> &Path {
+        Path::new(&self.root_path)
+    }
+
+    /// `paths` if given, otherwise every note in the vault.
+    pub(crate) fn file_list(&self, paths: Option<&[String]>) -> Vec<String> {
+        paths.map_or_else(|| self.file_paths.clone(), <[String]>::to_vec)
+    }
+
+    /// Bring the index up to date for `paths` and persist it (see `save_index`).
+    /// Returns true if any file was re-indexed.
+    pub fn refresh_index(&mut self, paths: &[String]) -> bool {
+        let changed = self.index.refresh(Path::new(&self.root_path), paths);
+        self.save_index();
+        changed
+    }
+
+    /// Re-index one file after it was written or created.
+    pub fn reindex_file(&mut self, rel: &str) {
+        self.index.update_file(Path::new(&self.root_path), rel);
+        self.save_index();
+    }
+
+    /// Drop one file from the index after it was deleted.
+    pub fn forget_file(&mut self, rel: &str) {
+        self.index.remove_file(rel);
+        self.save_index();
+    }
+
+    /// Add a new note to `file_paths` (keeping it sorted) and index it.
+    pub(crate) fn add_file(&mut self, rel: &str) {
+        self.reindex_file(rel);
+        if !self.file_paths.iter().any(|p| p == rel) {
+            self.file_paths.push(rel.to_string());
+            self.file_paths.sort();
+        }
+    }
+
+    /// Remove a note from `file_paths` and the index.
+    pub(crate) fn remove_file(&mut self, rel: &str) {
+        self.forget_file(rel);
+        self.file_paths.retain(|p| p != rel);
+    }
+
+    /// Save the index if the last save was a while ago; see `VaultIndex::save_if_due`.
+    /// A failed save only costs the next startup a rebuild, so it is logged rather than
+    /// returned.
+    fn save_index(&mut self) {
+        if !self.persist_index {
+            return;
+        }
+        if let Err(e) = self.index.save_if_due(Path::new(&self.root_path)) {
+            tracing::warn!("Could not save vault index: {}", e);
+        }
+    }
+
+    /// Save any index changes not saved yet. Runs when the vault is dropped.
+    pub fn flush_index(&mut self) {
+        if !self.persist_index {
+            return;
+        }
+        if let Err(e) = self.index.save(Path::new(&self.root_path)) {
+            tracing::warn!("Could not save vault index: {}", e);
+        }
+    }
+}
+
+impl Drop for Vault {
+    fn drop(&mut self) {
+        self.flush_index();
+    }
+}
+
+#[derive(Serialize)]
+pub struct OpenVaultResult {
+    pub root_path: String,
+    pub file_paths: Vec<String>,
+}
+
+/// Collects all .md file paths under dir (relative to root). Used by open_vault and tests.
+/// The app's own `.ledger/` directory, symlinked folders and broken links are skipped
+/// (as in `list_tree_impl`).
+pub fn collect_md_paths(dir: &Path, root: &Path, out: &mut Vec<String>) -> std::io::Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() && path.is_dir() {
+            continue;
+        }
+        if file_type.is_dir() {
+            if entry.file_name() == LEDGER_DIR {
+                continue;
+            }
+            collect_md_paths(&path, root, out)?;
+        } else if path.extension().is_some_and(|e| e == "md") && path.exists() {
+            if let Ok(rel) = path.strip_prefix(root) {
+                out.push(rel.to_string_lossy().into_owned());
+            } else {
+                out.push(path.to_string_lossy().into_owned());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Core vault-open logic without an index. Used by `Vault::open` and integration tests.
+pub fn open_vault_impl(path: &str) -> Result<OpenVaultResult, LedgerError> {
+    let root = Path::new(path);
+    if !root.exists() {
+        return Err(LedgerError::NotFound {
+            path: path.to_string(),
+        });
+    }
+    if !root.is_dir() {
+        return Err(LedgerError::invalid_path(path, "not a directory"));
+    }
+    let root_path_canon = std::fs::canonicalize(root).map_err(|e| LedgerError::io(path, e))?;
+    let root_path = root_path_canon.to_string_lossy().into_owned();
+
+    let mut file_paths = Vec::new();
+    collect_md_paths(
+        Path::new(&root_path),
+        Path::new(&root_path),
+        &mut file_paths,
+    )
+    .map_err(|e| LedgerError::io(path, e))?;
+
+    file_paths.sort();
+
+    Ok(OpenVaultResult {
+        root_path,
+        file_paths,
+    })
+}
@@ -0,0 +1,368 @@
+//! `VaultPath`: a path inside the vault, checked once before any file operation.
+//!
+//! Every operation that touches a note or folder by a caller-supplied path goes through
+//! `VaultPath::new` (or `VaultPath::for_create` when the path may name something new),
+//! which
+//! - normalizes `/` and `\` separators and drops empty and `.` components,
+//! - rejects absolute paths (`/x`, `\\server\x`, `C:\x`, `C:x`), `..` components and
+//!   NUL characters,
+//! - rejects the app's `.ledger/` folder,
+//! - resolves symlinks in the part of the path that exists and rejects it if they lead
+//!   outside the vault, or if one is dangling (writing through it would create its
+//!   target wherever it points).
+//!
+//! `for_create` also rejects new names Windows cannot store (`CON`, `aux.md`,
+//! `10:30.md`, trailing dots or spaces), so a vault stays usable when synced to a
+//! Windows machine. Notes that already have such a name can still be opened, moved and
+//! deleted.
+
+use crate::error::LedgerError;
+use crate::index::LEDGER_DIR;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf, MAIN_SEPARATOR_STR};
+
+/// Device names Windows reserves in every folder, with or without an extension.
+const WINDOWS_RESERVED: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM0", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7",
+    "COM8", "COM9", "COM¹", "COM²", "COM³", "LPT0", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6",
+    "LPT7", "LPT8", "LPT9", "LPT¹", "LPT²", "LPT³",
+];
+
+/// Characters Windows does not allow in file names (besides control characters).
+const WINDOWS_INVALID_CHARS: &[char] = &['<', '>', ':', '"', '|', '?', '*'];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultPath {
+    /// Canonical vault root.
+    root: PathBuf,
+    /// Normalized vault-relative path, with platform separators like `Vault::file_paths`.
+    rel: String,
+    /// Location on disk, with symlinks in the existing part resolved.
+    full: PathBuf,
+    /// Like `full`, except that a symlink in the last component is not followed.
+    entry: PathBuf,
+}
+
+impl VaultPath {
+    /// Check `path` (vault-relative) against the vault at `root`. Fails with
+    /// `OutsideVault` for absolute paths and symlinks out of the vault, and with
+    /// `InvalidPath` for everything else listed in the module docs.
+    pub fn new(root: &Path, path: &str) -> Result<VaultPath, LedgerError> {
+        Self::with_missing(root, path).map(|(p, _)| p)
+    }
+
+    /// Like `new`, for a path that may be created: a new note or folder, or where one is
+    /// moved or restored to. Components that do not exist yet must also be names Windows
+    /// can store.
+    pub fn for_create(root: &Path, path: &str) -> Result<VaultPath, LedgerError> {
+        let (vault_path, missing) = Self::with_missing(root, path)?;
+        for part in missing {
+            if let Err(reason) = check_name(&part) {
+                return Err(LedgerError::invalid_path(
+                    path,
+                    format!("{:?} {}", part, reason),
+                ));
+            }
+        }
+        Ok(vault_path)
+    }
+
+    /// The checked path, and its components that do not exist on disk.
+    fn with_missing(root: &Path, path: &str) -> Result<(VaultPath, Vec<String>), LedgerError> {
+        let parts = normalize(path)?;
+        let root = root
+            .canonicalize()
+            .map_err(|e| LedgerError::io(&root.to_string_lossy(), e))?;
+        let resolved = resolve(&root, &parts, path)?;
+        let missing = parts[resolved.existing..]
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        let vault_path = VaultPath {
+            root,
+            rel: parts.join(MAIN_SEPARATOR_STR),
+            full: resolved.full,
+            entry: resolved.entry,
+        };
+        Ok((vault_path, missing))
+    }
+
+    /// The normalized vault-relative path.
+    pub fn as_str(&self) -> &str {
+        &self.rel
+    }
+
+    /// Where the path is on disk, with symlinks resolved.
+    pub fn full(&self) -> &Path {
+        &self.full
+    }
+
+    /// Where the path itself is on disk: `full()`, unless the last component is a
+    /// symlink, which this points at rather than its target.
+    pub fn entry(&self) -> &Path {
+        &self.entry
+    }
+
+    /// The canonical vault root.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// `full()` relative to the vault root. Differs from `as_str()` when the path goes
+    /// through a symlink.
+    pub fn resolved(&self) -> String {
+        self.full
+            .strip_prefix(&self.root)
+            .unwrap_or(&self.full)
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    pub fn exists(&self) -> bool {
+        self.full.exists()
+    }
+
+    /// Create missing parent folders, then check again that they are inside the vault
+    /// (a folder could have been replaced by a symlink since `new`).
+    pub fn create_parents(&self) -> Result<(), LedgerError> {
+        let Some(parent) = self.full.parent() else {
+            return Ok(());
+        };
+        std::fs::create_dir_all(parent).map_err(|e| LedgerError::io(&self.rel, e))?;
+        let parent = parent
+            .canonicalize()
+            .map_err(|e| LedgerError::io(&self.rel, e))?;
+        if !parent.starts_with(&self.root) {
+            return Err(self.outside());
+        }
+        Ok(())
+    }
+
+    fn outside(&self) -> LedgerError {
+        LedgerError::OutsideVault {
+            path: self.rel.clone(),
+        }
+    }
+}
+
+impl fmt::Display for VaultPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.rel)
+    }
+}
+
+/// Split `path` into its components, rejecting anything that is not a plain relative
+/// path.
+fn normalize(path: &str) -> Result<Vec<&str>, LedgerError> {
+    let invalid = |reason: String| Err(LedgerError::invalid_path(path, reason));
+    if path.starts_with(['/', '\\']) || has_drive_prefix(path) {
+        return Err(LedgerError::OutsideVault {
+            path: path.to_string(),
+        });
+    }
+    if path.contains('\0') {
+        return invalid("must not contain a NUL character".to_string());
+    }
+    let parts: Vec<&str> = path
+        .split(['/', '\\'])
+        .filter(|c| !c.is_empty() && *c != ".")
+        .collect();
+    let Some(first) = parts.first() else {
+        return invalid("must not be empty".to_string());
+    };
+    if parts.contains(&"..") {
+        return invalid("must not contain ..".to_string());
+    }
+    if first.eq_ignore_ascii_case(LEDGER_DIR) {
+        return invalid(format!("{} is reserved for app data", LEDGER_DIR));
+    }
+    Ok(parts)
+}
+
+/// `C:` at the start: a drive-absolute or drive-relative Windows path.
+fn has_drive_prefix(path: &str) -> bool {
+    let b = path.as_bytes();
+    b.len() >= 2 && b[0].is_ascii_alphabetic() && b[1] == b':'
+}
+
+/// Check one path component against Windows' naming rules.
+fn check_name(name: &str) -> Result<(), &'static str> {
+    if name
+        .chars()
+        .any(|c| c.is_control() || WINDOWS_INVALID_CHARS.contains(&c))
+    {
+        return Err("contains a character Windows does not allow");
+    }
+    if name.ends_with(['.', ' ']) {
+        return Err("must not end with a dot or space");
+    }
+    // `con.md` and `CON .txt` name the device too.
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    if WINDOWS_RESERVED
+        .iter()
+        .any(|r| r.eq_ignore_ascii_case(stem))
+    {
+        return Err("is a reserved name on Windows");
+    }
+    Ok(())
+}
+
+struct Resolved {
+    full: PathBuf,
+    entry: PathBuf,
+    /// How many leading components exist.
+    existing: usize,
+}
+
+/// Join `parts` onto `root`, canonicalizing every component that exists so that
+/// symlinks (and Windows junctions) are followed and checked. Components after the
+/// first missing one cannot be links.
+fn resolve(root: &Path, parts: &[&str], path: &str) -> Result<Resolved, LedgerError> {
+    let mut full = root.to_path_buf();
+    let mut entry = full.clone();
+    let mut existing = 0;
+    let total = parts.len();
+    let mut parts = parts.iter();
+    for part in parts.by_ref() {
+        let next = full.join(part);
+        match std::fs::symlink_metadata(&next) {
+            Ok(_) => existing += 1,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                full = next;
+                break;
+            }
+            Err(e) => return Err(LedgerError::io(path, e)),
+        }
+        entry = next.clone();
+        full = match next.canonicalize() {
+            Ok(c) => c,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Err(LedgerError::invalid_path(path, "broken symlink"))
+            }
+            Err(e) => return Err(LedgerError::io(path, e)),
+        };
+        if !full.starts_with(root) {
+            return Err(LedgerError::OutsideVault {
+                path: path.to_string(),
+            });
+        }
+    }
+    for part in parts {
+        full.push(part);
+    }
+    if existing < total {
+        entry = full.clone();
+    }
+    Ok(Resolved {
+        full,
+        entry,
+        existing,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reason(path: &str) -> String {
+        match normalize(path) {
+            Err(LedgerError::InvalidPath { reason, .. }) => reason,
+            other => panic!("expected InvalidPath for {:?}, got {:?}", path, other),
+        }
+    }
+
+    #[test]
+    fn normalizes_separators_and_dots() {
+        assert_eq!(normalize("a/b.md").unwrap(), vec!["a", "b.md"]);
+        assert_eq!(normalize("a\\b.md").unwrap(), vec!["a", "b.md"]);
+        assert_eq!(normalize("./a//./b.md/").unwrap(), vec!["a", "b.md"]);
+        // `..` inside a name is fine.
+        assert_eq!(normalize("v1..v2.md").unwrap(), vec!["v1..v2.md"]);
+    }
+
+    #[test]
+    fn rejects_absolute_and_escaping_paths() {
+        for p in [
+            "/etc/passwd",
+            "\\x.md",
+            "\\\\server\\share\\x.md",
+            "C:\\x.md",
+            "c:x.md",
+        ] {
+            assert!(
+                matches!(normalize(p), Err(LedgerError::OutsideVault { .. })),
+                "{}",
+                p
+            );
+        }
+        for p in ["..", "../x.md", "a/../../x.md", "a\\..\\..\\x.md", "./a/.."] {
+            assert_eq!(reason(p), "must not contain ..", "{}", p);
+        }
+        for p in ["", "/", "./", "."] {
+            let err = normalize(p).unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    LedgerError::InvalidPath { .. } | LedgerError::OutsideVault { .. }
+                ),
+                "{}",
+                p
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_app_data_and_nul() {
+        for p in [".ledger", ".ledger/index.json", "./.LEDGER/x.md"] {
+            assert!(reason(p).contains("reserved for app data"), "{}", p);
+        }
+        assert!(reason("notes/nul\0.md").contains("NUL character"));
+    }
+
+    #[test]
+    fn new_names_must_be_valid_on_windows() {
+        let root = std::env::temp_dir().join("ledger_test_vault_path_names");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        let reason = |p: &str| match VaultPath::for_create(&root, p) {
+            Err(LedgerError::InvalidPath { reason, .. }) => reason,
+            other => panic!("expected InvalidPath for {:?}, got {:?}", p, other),
+        };
+        for p in [
+            "CON",
+            "con.md",
+            "a/Aux.tar.gz",
+            "NUL .md",
+            "com1.md",
+            "LPT9",
+            "lpt¹.md",
+        ] {
+            assert!(reason(p).contains("reserved name"), "{}", p);
+        }
+        for p in [
+            "notes/10:30.md",
+            "what?.md",
+            "x*.md",
+            "a|b",
+            "<a>.md",
+            "quote\".md",
+            "tab\t.md",
+        ] {
+            assert!(reason(p).contains("character"), "{}", p);
+        }
+        for p in ["trailing..md.", "space.md ", "dir./x.md"] {
+            assert!(reason(p).contains("dot or space"), "{}", p);
+        }
+        assert!(VaultPath::for_create(&root, "console.md").is_ok());
+        assert!(VaultPath::for_create(&root, "com10.md").is_ok());
+
+        // Only names that do not exist yet are checked; looking one up is fine.
+        std::fs::create_dir(root.join("aux")).unwrap();
+        assert!(VaultPath::new(&root, "what?.md").is_ok());
+        assert!(VaultPath::for_create(&root, "aux/x.md").is_ok());
+        assert!(reason("aux/what?.md").contains("character"));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
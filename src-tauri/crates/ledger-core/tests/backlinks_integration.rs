@@ -0,0 +1,56 @@
+//! Integration test: backlinks follow edits and point at items through assigned block ids.
+
+use ledger_core::Vault;
+use std::fs;
+
+#[test]
+fn backlinks_track_edits_and_block_ids() {
+    let temp = std::env::temp_dir().join("ledger_test_backlinks_integration");
+    let _ = fs::remove_dir_all(&temp);
+    fs::create_dir_all(&temp).unwrap();
+    fs::write(
+        temp.join("adr.md"),
+        "- Use SQLite #decision\n- Keep backups\n",
+    )
+    .unwrap();
+
+    let mut vault = Vault::open(temp.to_str().unwrap()).unwrap();
+    assert!(vault.backlinks("adr.md", None).unwrap().is_empty());
+
+    let block_id = vault.assign_block_id("adr.md", "0").unwrap();
+    vault.create_file("review.md").unwrap();
+    vault
+        .write_file(
+            "review.md",
+            &format!(
+                "- Q3 review\n  - Revisit [[adr^{}]] #followup\n  - Also [[ADR.md]]\n",
+                block_id
+            ),
+            None,
+        )
+        .unwrap();
+
+    let all = vault.backlinks("adr.md", None).unwrap();
+    assert_eq!(all.len(), 2);
+    assert!(all.iter().all(|r| r.file_path == "review.md"));
+    assert_eq!(all[0].parent_path.as_deref(), Some("Q3 review"));
+    assert_eq!(all[0].effective_tags, vec!["followup"]);
+
+    let to_item = vault.backlinks("adr.md", Some(&block_id)).unwrap();
+    assert_eq!(to_item.len(), 1);
+    assert_eq!(
+        to_item[0].node.links[0].block_id.as_deref(),
+        Some(&*block_id)
+    );
+
+    // Removing the link removes the backlink.
+    vault
+        .write_file("review.md", "- Q3 review\n  - Also [[ADR.md]]\n", None)
+        .unwrap();
+    assert!(vault
+        .backlinks("adr.md", Some(&block_id))
+        .unwrap()
+        .is_empty());
+
+    let _ = fs::remove_dir_all(&temp);
+}
@@ -0,0 +1,43 @@
+//! Integration test: assign_block_id writes a `^block-id` marker that survives edits.
+
+use ledger_core::{
+    assign_block_id_impl, open_vault_impl, read_file_impl, write_file_impl, LedgerError,
+};
+use std::fs;
+
+#[test]
+fn assign_block_id_writes_marker_and_is_idempotent() {
+    let temp = std::env::temp_dir().join("ledger_test_block_id");
+    let _ = fs::remove_dir_all(&temp);
+    fs::create_dir_all(&temp).unwrap();
+
+    let result = open_vault_impl(temp.to_str().unwrap()).unwrap();
+    let root = &result.root_path;
+
+    write_file_impl(
+        root,
+        "adr.md",
+        "- Use SQLite #decision\n  - Revisit in Q3\n",
+    )
+    .unwrap();
+
+    let block_id = assign_block_id_impl(root, "adr.md", "0").unwrap();
+    let content = read_file_impl(root, "adr.md").unwrap();
+    assert!(content.starts_with(&format!("- Use SQLite #decision ^{}\n", block_id)));
+
+    // Insert an item above; the block id still resolves and is not rewritten.
+    write_file_impl(root, "adr.md", &format!("- New item\n{}", content)).unwrap();
+    let again = assign_block_id_impl(root, "adr.md", &block_id).unwrap();
+    assert_eq!(again, block_id);
+
+    let err = assign_block_id_impl(root, "adr.md", "missing").unwrap_err();
+    assert_eq!(
+        err,
+        LedgerError::NodeNotFound {
+            path: "adr.md".into(),
+            node_id: "missing".into(),
+        }
+    );
+
+    let _ = fs::remove_dir_all(&temp);
+}
@@ -0,0 +1,49 @@
+//! Integration test: write_file with an expected version detects changes made on disk.
+
+use ledger_core::{
+    open_vault_impl, read_file_versioned_impl, write_file_checked_impl, LedgerError,
+};
+use std::fs;
+
+#[test]
+fn write_with_stale_version_returns_conflict() {
+    let temp = std::env::temp_dir().join("ledger_test_conflict");
+    let _ = fs::remove_dir_all(&temp);
+    fs::create_dir_all(&temp).unwrap();
+    fs::write(temp.join("a.md"), "- one").unwrap();
+    let root = open_vault_impl(temp.to_str().unwrap()).unwrap().root_path;
+
+    let read = read_file_versioned_impl(&root, "a.md").unwrap();
+    assert_eq!(read.content, "- one");
+
+    // Saving with the version just read succeeds and returns the next version.
+    let v2 = write_file_checked_impl(&root, "a.md", "- two", Some(&read.version)).unwrap();
+    assert_eq!(read_file_versioned_impl(&root, "a.md").unwrap().version, v2);
+
+    // Another program edits the file; saving with the old version fails.
+    fs::write(temp.join("a.md"), "- from sync").unwrap();
+    match write_file_checked_impl(&root, "a.md", "- three", Some(&v2)) {
+        Err(e @ LedgerError::Conflict { .. }) => {
+            let json = serde_json::to_value(&e).unwrap();
+            assert_eq!(json["code"], "Conflict");
+            assert_eq!(json["path"], "a.md");
+            assert_eq!(json["content"], "- from sync");
+        }
+        other => panic!("expected conflict, got {:?}", other),
+    }
+    assert_eq!(
+        fs::read_to_string(temp.join("a.md")).unwrap(),
+        "- from sync"
+    );
+
+    // A deleted file is a conflict with no content; no expected version overwrites.
+    fs::remove_file(temp.join("a.md")).unwrap();
+    match write_file_checked_impl(&root, "a.md", "- three", Some(&v2)) {
+        Err(LedgerError::Conflict { content, .. }) => assert!(content.is_none()),
+        other => panic!("expected conflict, got {:?}", other),
+    }
+    write_file_checked_impl(&root, "a.md", "- three", None).unwrap();
+    assert_eq!(fs::read_to_string(temp.join("a.md")).unwrap(), "- three");
+
+    let _ = fs::remove_dir_all(&temp);
+}
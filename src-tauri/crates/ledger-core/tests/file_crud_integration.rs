@@ -0,0 +1,88 @@
+//! Integration test: create_file, read_file, write_file, delete_file (to trash), move_file.
+
+use ledger_core::{
+    create_file_impl, delete_file_impl, list_trash_impl, move_file_impl, open_vault_impl,
+    read_file_impl, restore_from_trash_impl, write_file_impl, LedgerError, RestoreConflict,
+};
+use std::fs;
+
+#[test]
+fn file_crud_create_read_write_delete() {
+    let temp = std::env::temp_dir().join("ledger_test_crud");
+    let _ = fs::remove_dir_all(&temp);
+    fs::create_dir_all(&temp).unwrap();
+
+    let result = open_vault_impl(temp.to_str().unwrap()).unwrap();
+    let root = &result.root_path;
+
+    create_file_impl(root, "t.md").unwrap();
+    let content = read_file_impl(root, "t.md").unwrap();
+    assert_eq!(content, "");
+
+    write_file_impl(root, "t.md", "hi").unwrap();
+    let content = read_file_impl(root, "t.md").unwrap();
+    assert_eq!(content, "hi");
+
+    let entry = delete_file_impl(root, "t.md").unwrap();
+    let err = read_file_impl(root, "t.md").unwrap_err();
+    assert_eq!(
+        err,
+        LedgerError::NotFound {
+            path: "t.md".into()
+        }
+    );
+
+    // Deleted files go to the trash and can be restored.
+    assert_eq!(list_trash_impl(root).unwrap()[0].original_path, "t.md");
+    let restored = restore_from_trash_impl(root, &entry.id, RestoreConflict::Fail).unwrap();
+    assert_eq!(restored, "t.md");
+    assert_eq!(read_file_impl(root, "t.md").unwrap(), "hi");
+
+    let _ = fs::remove_dir_all(&temp);
+}
+
+#[test]
+fn move_file_refuses_overwrite_and_updates_links() {
+    let temp = std::env::temp_dir().join("ledger_test_move");
+    let _ = fs::remove_dir_all(&temp);
+    fs::create_dir_all(&temp).unwrap();
+    let root = open_vault_impl(temp.to_str().unwrap()).unwrap().root_path;
+
+    write_file_impl(&root, "plan.md", "- Plan").unwrap();
+    write_file_impl(&root, "notes/log.md", "- See [plan](../plan.md#goals)").unwrap();
+    write_file_impl(&root, "taken.md", "- Taken").unwrap();
+    let paths: Vec<String> = ["notes/log.md", "plan.md", "taken.md"]
+        .map(String::from)
+        .to_vec();
+
+    let err = move_file_impl(&root, "plan.md", "taken.md", false, &paths).unwrap_err();
+    assert_eq!(
+        err,
+        LedgerError::AlreadyExists {
+            path: "taken.md".into()
+        }
+    );
+    let code = |to: &str| {
+        move_file_impl(&root, "plan.md", to, false, &paths)
+            .unwrap_err()
+            .code()
+    };
+    assert_eq!(code("../plan.md"), "InvalidPath");
+    assert_eq!(code("/tmp/plan.md"), "OutsideVault");
+    assert_eq!(code("plan.txt"), "InvalidPath");
+
+    let result = move_file_impl(&root, "plan.md", "archive/2024/plan.md", true, &paths).unwrap();
+    assert_eq!(result.links_updated, vec!["notes/log.md".to_string()]);
+    assert!(result.link_errors.is_empty());
+    assert_eq!(
+        read_file_impl(&root, "archive/2024/plan.md").unwrap(),
+        "- Plan"
+    );
+    assert!(read_file_impl(&root, "plan.md").is_err());
+    assert_eq!(
+        read_file_impl(&root, "notes/log.md").unwrap(),
+        "- See [plan](../archive/2024/plan.md#goals)"
+    );
+
+    let _ = fs::remove_dir_all(&temp);
+}
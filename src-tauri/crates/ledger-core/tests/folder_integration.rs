@@ -0,0 +1,93 @@
+//! Integration test: folder create/rename/delete and list_tree.
+
+use ledger_core::{
+    create_folder_impl, delete_folder_impl, list_tree_impl, open_vault_impl, rename_folder_impl,
+    write_file_impl, EntryKind, TreeEntry,
+};
+use std::fs;
+
+fn names(entries: &[TreeEntry]) -> Vec<&str> {
+    entries.iter().map(|e| e.name.as_str()).collect()
+}
+
+#[test]
+fn folder_crud_and_tree() {
+    let temp = std::env::temp_dir().join("ledger_test_folders");
+    let _ = fs::remove_dir_all(&temp);
+    fs::create_dir_all(&temp).unwrap();
+    let root = open_vault_impl(temp.to_str().unwrap()).unwrap().root_path;
+
+    create_folder_impl(&root, "empty/inner").unwrap();
+    assert!(create_folder_impl(&root, "empty").is_err());
+    assert!(create_folder_impl(&root, ".ledger/x").is_err());
+    assert!(create_folder_impl(&root, "../outside").is_err());
+    write_file_impl(&root, "projects/a.md", "- a").unwrap();
+    write_file_impl(&root, "projects/sub/b.md", "- b").unwrap();
+    write_file_impl(&root, "projects/readme.txt", "x").unwrap();
+    write_file_impl(&root, "top.md", "- top").unwrap();
+
+    let tree = list_tree_impl(&root).unwrap();
+    assert_eq!(names(&tree), vec!["empty", "projects", "top.md"]);
+    assert_eq!(names(&tree[0].children), vec!["inner"]);
+    let projects = &tree[1];
+    assert!(matches!(projects.kind, EntryKind::Folder));
+    assert_eq!(names(&projects.children), vec!["sub", "a.md"]);
+    assert_eq!(projects.children[1].path, "projects/a.md");
+    assert_eq!(projects.children[1].size, Some(3));
+    assert!(tree[2].modified_ms.is_some());
+
+    let paths: Vec<String> = ["projects/a.md", "projects/sub/b.md", "top.md"]
+        .map(String::from)
+        .to_vec();
+    assert!(rename_folder_impl(&root, "projects", "projects/sub/x", &paths).is_err());
+    assert!(rename_folder_impl(&root, "projects", "empty", &paths).is_err());
+    let moved = rename_folder_impl(&root, "projects", "archive/projects", &paths).unwrap();
+    assert_eq!(
+        moved,
+        vec![
+            (
+                "projects/a.md".to_string(),
+                "archive/projects/a.md".to_string()
+            ),
+            (
+                "projects/sub/b.md".to_string(),
+                "archive/projects/sub/b.md".to_string()
+            ),
+        ]
+    );
+    assert!(temp.join("archive/projects/sub/b.md").is_file());
+
+    let paths: Vec<String> = moved.into_iter().map(|(_, new)| new).collect();
+    assert!(delete_folder_impl(&root, "", &paths).is_err());
+    let removed = delete_folder_impl(&root, "archive", &paths).unwrap();
+    assert_eq!(removed.len(), 2);
+    assert!(!temp.join("archive").exists());
+
+    let _ = fs::remove_dir_all(&temp);
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinked_folders_are_not_followed() {
+    use std::os::unix::fs::symlink;
+
+    let temp = std::env::temp_dir().join("ledger_test_folder_links");
+    let _ = fs::remove_dir_all(&temp);
+    fs::create_dir_all(temp.join("vault/notes")).unwrap();
+    fs::create_dir_all(temp.join("outside")).unwrap();
+    fs::write(temp.join("vault/notes/a.md"), "- a").unwrap();
+    fs::write(temp.join("outside/secret.md"), "- secret").unwrap();
+    symlink(".", temp.join("vault/loop")).unwrap();
+    symlink(temp.join("outside"), temp.join("vault/out")).unwrap();
+    symlink("notes/a.md", temp.join("vault/alias.md")).unwrap();
+    symlink("missing.md", temp.join("vault/broken.md")).unwrap();
+
+    let opened = open_vault_impl(temp.join("vault").to_str().unwrap()).unwrap();
+    assert_eq!(opened.file_paths, vec!["alias.md", "notes/a.md"]);
+
+    let tree = list_tree_impl(&opened.root_path).unwrap();
+    assert_eq!(names(&tree), vec!["notes", "alias.md"]);
+    assert_eq!(tree[1].size, Some(3));
+
+    let _ = fs::remove_dir_all(&temp);
+}
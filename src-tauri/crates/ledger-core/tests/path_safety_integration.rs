@@ -0,0 +1,325 @@
+//! Integration test: hostile paths are rejected by every file operation, and nothing is
+//! read or written outside the vault.
+
+use ledger_core::{
+    create_file_impl, create_folder_impl, delete_file_impl, delete_folder_impl, move_file_impl,
+    read_file_impl, rename_folder_impl, restore_from_trash_impl, write_file_impl, LedgerError,
+    RestoreConflict, Vault,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A vault with `notes/a.md`, next to an `outside/` folder holding `secret.md`.
+fn setup(name: &str) -> (PathBuf, String) {
+    let temp = std::env::temp_dir().join(name);
+    let _ = fs::remove_dir_all(&temp);
+    fs::create_dir_all(temp.join("vault/notes")).unwrap();
+    fs::create_dir_all(temp.join("outside")).unwrap();
+    fs::write(temp.join("vault/notes/a.md"), "- a").unwrap();
+    fs::write(temp.join("outside/secret.md"), "- secret").unwrap();
+    let root = fs::canonicalize(temp.join("vault"))
+        .unwrap()
+        .to_string_lossy()
+        .into_owned();
+    (temp, root)
+}
+
+fn code(result: Result<impl std::fmt::Debug, LedgerError>) -> &'static str {
+    result.unwrap_err().code()
+}
+
+/// Every operation taking a path, run with `path` in each position.
+fn all_operations(root: &str, path: &str) -> Vec<(&'static str, &'static str)> {
+    let paths = vec!["notes/a.md".to_string()];
+    vec![
+        ("read", code(read_file_impl(root, path))),
+        ("write", code(write_file_impl(root, path, "- x"))),
+        ("create", code(create_file_impl(root, path))),
+        ("delete", code(delete_file_impl(root, path))),
+        (
+            "move from",
+            code(move_file_impl(root, path, "b.md", false, &paths)),
+        ),
+        (
+            "move to",
+            code(move_file_impl(root, "notes/a.md", path, false, &paths)),
+        ),
+        ("create folder", code(create_folder_impl(root, path))),
+        (
+            "rename folder from",
+            code(rename_folder_impl(root, path, "x", &paths)),
+        ),
+        (
+            "rename folder to",
+            code(rename_folder_impl(root, "notes", path, &paths)),
+        ),
+        (
+            "delete folder",
+            code(delete_folder_impl(root, path, &paths)),
+        ),
+    ]
+}
+
+#[test]
+fn hostile_paths_are_rejected_everywhere() {
+    let (temp, root) = setup("ledger_test_path_safety");
+    let outside = temp.join("outside");
+
+    let cases = [
+        ("../outside/secret.md", "InvalidPath"),
+        ("notes/../../outside/secret.md", "InvalidPath"),
+        ("notes\\..\\..\\outside\\secret.md", "InvalidPath"),
+        ("./../outside/x.md", "InvalidPath"),
+        ("..", "InvalidPath"),
+        ("", "InvalidPath"),
+        ("./", "InvalidPath"),
+        (".ledger/index.json", "InvalidPath"),
+        (".Ledger/trash/x.md", "InvalidPath"),
+        ("notes/nul\0.md", "InvalidPath"),
+        ("/etc/passwd", "OutsideVault"),
+        ("\\\\server\\share\\x.md", "OutsideVault"),
+        ("C:\\Windows\\x.md", "OutsideVault"),
+        ("c:x.md", "OutsideVault"),
+    ];
+    for (path, expected) in cases {
+        for (op, got) in all_operations(&root, path) {
+            // The destination of a move must end in .md, which some cases do not.
+            let expected = if op == "move to" && !path.ends_with(".md") {
+                "InvalidPath"
+            } else {
+                expected
+            };
+            assert_eq!(got, expected, "{} {:?}", op, path);
+        }
+    }
+
+    // Nothing leaked out, and the vault is untouched.
+    let mut left: Vec<_> = fs::read_dir(&outside)
+        .unwrap()
+        .map(|e| e.unwrap().file_name())
+        .collect();
+    left.sort();
+    assert_eq!(left, vec!["secret.md"]);
+    assert_eq!(
+        fs::read_to_string(Path::new(&root).join("notes/a.md")).unwrap(),
+        "- a"
+    );
+    assert!(!Path::new(&root).join(".ledger").exists());
+
+    let _ = fs::remove_dir_all(&temp);
+}
+
+#[test]
+fn windows_names_are_checked_only_when_created() {
+    let (temp, root) = setup("ledger_test_path_windows_names");
+    let vault = Path::new(&root);
+    let paths = vec!["notes/a.md".to_string()];
+
+    for path in [
+        "CON.md",
+        "notes/aux",
+        "notes/what?.md",
+        "notes/trailing.md.",
+    ] {
+        assert_eq!(
+            code(create_file_impl(&root, path)),
+            "InvalidPath",
+            "{}",
+            path
+        );
+        assert_eq!(
+            code(write_file_impl(&root, path, "- x")),
+            "InvalidPath",
+            "{}",
+            path
+        );
+        assert_eq!(
+            code(create_folder_impl(&root, path)),
+            "InvalidPath",
+            "{}",
+            path
+        );
+        assert_eq!(
+            code(rename_folder_impl(&root, "notes", path, &paths)),
+            "InvalidPath",
+            "{}",
+            path
+        );
+        // Looking up a name that does not exist is just not found.
+        assert_eq!(code(read_file_impl(&root, path)), "NotFound", "{}", path);
+        assert_eq!(code(delete_file_impl(&root, path)), "NotFound", "{}", path);
+    }
+    assert_eq!(
+        code(move_file_impl(
+            &root,
+            "notes/a.md",
+            "notes/10:30.md",
+            false,
+            &paths
+        )),
+        "InvalidPath"
+    );
+    assert!(!vault.join("CON.md").exists());
+
+    // Notes that already have such names (made on another system) stay usable.
+    fs::create_dir_all(vault.join("meetings")).unwrap();
+    fs::write(vault.join("meetings/2026-10-18 10:30.md"), "- standup").unwrap();
+    fs::write(vault.join("What next?.md"), "- ideas").unwrap();
+    fs::write(vault.join("aux.md"), "- aux").unwrap();
+    assert_eq!(
+        read_file_impl(&root, "meetings/2026-10-18 10:30.md").unwrap(),
+        "- standup"
+    );
+    write_file_impl(&root, "What next?.md", "- more ideas").unwrap();
+    assert_eq!(
+        read_file_impl(&root, "What next?.md").unwrap(),
+        "- more ideas"
+    );
+    assert_eq!(code(create_file_impl(&root, "aux.md")), "AlreadyExists");
+    move_file_impl(&root, "What next?.md", "notes/next.md", false, &paths).unwrap();
+    assert_eq!(
+        read_file_impl(&root, "notes/next.md").unwrap(),
+        "- more ideas"
+    );
+    let entry = delete_file_impl(&root, "aux.md").unwrap();
+    assert_eq!(entry.original_path, "aux.md");
+    assert!(!vault.join("aux.md").exists());
+    // Restoring would create the name again.
+    assert_eq!(
+        code(restore_from_trash_impl(
+            &root,
+            &entry.id,
+            RestoreConflict::Fail
+        )),
+        "InvalidPath"
+    );
+
+    let _ = fs::remove_dir_all(&temp);
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinks_out_of_the_vault_are_rejected() {
+    use std::os::unix::fs::symlink;
+
+    let (temp, root) = setup("ledger_test_path_symlinks");
+    let outside = temp.join("outside");
+    let vault = Path::new(&root);
+    symlink(&outside, vault.join("link")).unwrap();
+    symlink(outside.join("secret.md"), vault.join("leak.md")).unwrap();
+    symlink(outside.join("new.md"), vault.join("dangling.md")).unwrap();
+    symlink(vault.join("notes/a.md"), vault.join("alias.md")).unwrap();
+
+    for path in [
+        "leak.md",
+        "link/secret.md",
+        "link/new.md",
+        "link/sub/new.md",
+    ] {
+        for (op, got) in all_operations(&root, path) {
+            assert_eq!(got, "OutsideVault", "{} {:?}", op, path);
+        }
+    }
+    for (op, got) in all_operations(&root, "link") {
+        let expected = if op == "move to" {
+            "InvalidPath"
+        } else {
+            "OutsideVault"
+        };
+        assert_eq!(got, expected, "{} link", op);
+    }
+    // Writing through a dangling link would create its target outside the vault.
+    assert_eq!(
+        write_file_impl(&root, "dangling.md", "- x"),
+        Err(LedgerError::InvalidPath {
+            path: "dangling.md".into(),
+            reason: "broken symlink".into()
+        })
+    );
+    assert_eq!(code(create_file_impl(&root, "dangling.md")), "InvalidPath");
+    assert!(!outside.join("new.md").exists());
+    assert!(!outside.join("sub").exists());
+    assert_eq!(
+        fs::read_to_string(outside.join("secret.md")).unwrap(),
+        "- secret"
+    );
+
+    // A link to a note inside the vault is written through, not replaced.
+    write_file_impl(&root, "alias.md", "- via alias").unwrap();
+    assert!(fs::symlink_metadata(vault.join("alias.md"))
+        .unwrap()
+        .file_type()
+        .is_symlink());
+    assert_eq!(
+        fs::read_to_string(vault.join("notes/a.md")).unwrap(),
+        "- via alias"
+    );
+
+    // Deleting the link trashes the link, not the note it points at.
+    let entry = delete_file_impl(&root, "alias.md").unwrap();
+    assert_eq!(entry.original_path, "alias.md");
+    assert!(fs::symlink_metadata(vault.join("alias.md")).is_err());
+    assert_eq!(
+        fs::read_to_string(vault.join("notes/a.md")).unwrap(),
+        "- via alias"
+    );
+    restore_from_trash_impl(&root, &entry.id, RestoreConflict::Fail).unwrap();
+    assert_eq!(read_file_impl(&root, "alias.md").unwrap(), "- via alias");
+
+    // Moving the link moves the link, and never onto the note it points at.
+    let paths = vec!["alias.md".to_string(), "notes/a.md".to_string()];
+    assert_eq!(
+        code(move_file_impl(
+            &root,
+            "alias.md",
+            "notes/a.md",
+            false,
+            &paths
+        )),
+        "AlreadyExists"
+    );
+    move_file_impl(&root, "alias.md", "moved.md", false, &paths).unwrap();
+    assert!(fs::symlink_metadata(vault.join("alias.md")).is_err());
+    assert!(fs::symlink_metadata(vault.join("moved.md"))
+        .unwrap()
+        .file_type()
+        .is_symlink());
+    assert_eq!(read_file_impl(&root, "notes/a.md").unwrap(), "- via alias");
+    assert_eq!(read_file_impl(&root, "moved.md").unwrap(), "- via alias");
+
+    // Renaming a linked folder renames the link.
+    symlink(vault.join("notes"), vault.join("shortcut")).unwrap();
+    rename_folder_impl(&root, "shortcut", "renamed", &paths).unwrap();
+    assert!(fs::symlink_metadata(vault.join("renamed"))
+        .unwrap()
+        .file_type()
+        .is_symlink());
+    assert!(vault.join("notes/a.md").is_file());
+
+    let _ = fs::remove_dir_all(&temp);
+}
+
+#[test]
+fn paths_are_normalized() {
+    let (temp, root) = setup("ledger_test_path_normalize");
+
+    write_file_impl(&root, "./notes//b.md", "- b").unwrap();
+    assert_eq!(read_file_impl(&root, "notes/b.md").unwrap(), "- b");
+    // `..` inside a name is not a parent reference.
+    write_file_impl(&root, "v1..v2.md", "- v").unwrap();
+    assert_eq!(read_file_impl(&root, "v1..v2.md").unwrap(), "- v");
+
+    let mut vault = Vault::open(&root).unwrap();
+    vault.create_file("./notes/c.md").unwrap();
+    assert!(vault.file_paths.contains(
+        &Path::new("notes")
+            .join("c.md")
+            .to_string_lossy()
+            .into_owned()
+    ));
+    let moved = vault.move_file("notes//c.md", "./d.md", false).unwrap();
+    assert_eq!(moved.to, "d.md");
+    assert!(vault.file_paths.contains(&"d.md".to_string()));
+
+    let _ = fs::remove_dir_all(&temp);
+}
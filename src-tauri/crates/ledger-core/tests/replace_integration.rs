@@ -0,0 +1,108 @@
+//! Integration test: replace_in_vault previews changes, then applies a chosen subset.
+
+use ledger_core::{
+    open_vault_impl, read_file_impl, read_file_versioned_impl, replace_in_vault_impl,
+    write_file_impl, LedgerError, ReplaceSelection, SearchOptions,
+};
+use std::fs;
+
+#[test]
+fn preview_then_apply_subset() {
+    let temp = std::env::temp_dir().join("ledger_test_replace");
+    let _ = fs::remove_dir_all(&temp);
+    fs::create_dir_all(&temp).unwrap();
+    let root = open_vault_impl(temp.to_str().unwrap()).unwrap().root_path;
+
+    write_file_impl(&root, "a.md", "- Apollo kickoff\n- apollo retro\n").unwrap();
+    write_file_impl(&root, "b.md", "- Apollo budget\n").unwrap();
+    write_file_impl(&root, "c.md", "- unrelated\n").unwrap();
+    let paths: Vec<String> = ["a.md", "b.md", "c.md"].map(String::from).to_vec();
+    let options = SearchOptions::default();
+
+    let preview = replace_in_vault_impl(&root, &paths, "apollo", "Artemis", options, None).unwrap();
+    assert_eq!(preview.len(), 2);
+    assert_eq!(preview[0].changes.len(), 2);
+    assert_eq!(preview[0].changes[1].preview, "- Artemis retro");
+    assert!(preview.iter().all(|f| !f.applied));
+    // A dry run writes nothing.
+    assert_eq!(
+        read_file_impl(&root, "a.md").unwrap(),
+        "- Apollo kickoff\n- apollo retro\n"
+    );
+
+    // b.md changes after the preview, so only a.md is applied.
+    write_file_impl(&root, "b.md", "- Apollo budget v2\n").unwrap();
+    let selection = vec![
+        ReplaceSelection {
+            file_path: "a.md".into(),
+            version: preview[0].version.clone(),
+            change_ids: Some(vec![0]),
+        },
+        ReplaceSelection {
+            file_path: "b.md".into(),
+            version: preview[1].version.clone(),
+            change_ids: None,
+        },
+    ];
+    let applied = replace_in_vault_impl(
+        &root,
+        &paths,
+        "apollo",
+        "Artemis",
+        options,
+        Some(&selection),
+    )
+    .unwrap();
+    assert!(applied[0].applied && applied[0].error.is_none());
+    assert!(!applied[1].applied);
+    assert!(matches!(
+        &applied[1].error,
+        Some(LedgerError::Conflict { path, .. }) if path == "b.md"
+    ));
+    assert_eq!(
+        read_file_impl(&root, "a.md").unwrap(),
+        "- Artemis kickoff\n- apollo retro\n"
+    );
+    assert_eq!(
+        read_file_impl(&root, "b.md").unwrap(),
+        "- Apollo budget v2\n"
+    );
+
+    // The version from read_file works as a selection version too.
+    let read = read_file_versioned_impl(&root, "a.md").unwrap();
+    let selection = vec![ReplaceSelection {
+        file_path: "a.md".into(),
+        version: read.version,
+        change_ids: None,
+    }];
+    let applied = replace_in_vault_impl(
+        &root,
+        &paths,
+        "apollo",
+        "Artemis",
+        options,
+        Some(&selection),
+    )
+    .unwrap();
+    assert!(applied[0].applied && applied[0].error.is_none());
+    assert_eq!(
+        read_file_impl(&root, "a.md").unwrap(),
+        "- Artemis kickoff\n- Artemis retro\n"
+    );
+
+    let err = replace_in_vault_impl(
+        &root,
+        &paths,
+        "(",
+        "x",
+        SearchOptions {
+            regex: true,
+            ..Default::default()
+        },
+        None,
+    )
+    .unwrap_err();
+    assert!(err.to_string().starts_with("Invalid regex"));
+
+    let _ = fs::remove_dir_all(&temp);
+}
@@ -0,0 +1,115 @@
+//! Integration test: search and query through `Vault`, end to end from files on disk.
+
+use ledger_core::{LedgerError, SearchOptions, TaskState, Vault};
+use std::fs;
+
+fn vault(name: &str) -> (std::path::PathBuf, Vault) {
+    let temp = std::env::temp_dir().join(name);
+    let _ = fs::remove_dir_all(&temp);
+    fs::create_dir_all(temp.join("projects")).unwrap();
+    fs::write(
+        temp.join("notes.md"),
+        "- Hello world #decision\n  - This is a test note\n- [ ] Follow up #ops",
+    )
+    .unwrap();
+    fs::write(
+        temp.join("projects/alpha.md"),
+        "- Project A\n  - Task 1 #task\n  - Task 2\n- Project B\n  - Task 3 #task #decision",
+    )
+    .unwrap();
+    let vault = Vault::open(temp.to_str().unwrap()).unwrap();
+    (temp, vault)
+}
+
+#[test]
+fn search_full_text_finds_matches_and_sees_new_content() {
+    let (temp, mut vault) = vault("ledger_test_core_search");
+
+    let results = vault
+        .search("hello", None, false, SearchOptions::default())
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].file_path, "notes.md");
+    assert_eq!(results[0].snippet_or_line, "- Hello world #decision");
+    assert_eq!(results[0].start_offset, Some(2));
+
+    assert!(vault
+        .search("no such text", None, false, SearchOptions::default())
+        .unwrap()
+        .is_empty());
+
+    // Writes through the vault are searchable straight away.
+    vault
+        .write_file("notes.md", "- Goodbye world", None)
+        .unwrap();
+    assert!(vault
+        .search("hello", None, false, SearchOptions::default())
+        .unwrap()
+        .is_empty());
+    let fuzzy = vault
+        .search("gdbye", None, true, SearchOptions::default())
+        .unwrap();
+    assert_eq!(fuzzy[0].file_path, "notes.md");
+
+    let _ = fs::remove_dir_all(&temp);
+}
+
+#[test]
+fn query_by_tag_and_query_language() {
+    let (temp, mut vault) = vault("ledger_test_core_query");
+    let alpha = std::path::Path::new("projects")
+        .join("alpha.md")
+        .to_string_lossy()
+        .into_owned();
+
+    let decisions = vault.query_by_tag(&["decision".to_string()], None, None, None, false);
+    let texts: Vec<&str> = decisions.iter().map(|r| r.node.text.as_str()).collect();
+    assert_eq!(
+        texts,
+        vec!["Hello world #decision", "Task 3 #task #decision"]
+    );
+    assert_eq!(decisions[1].parent_path.as_deref(), Some("Project B"));
+
+    // Scope: only items under "Project A" (node id 0 in alpha.md).
+    let scoped = vault.query_by_tag(
+        &["task".to_string()],
+        Some("0"),
+        Some(std::slice::from_ref(&alpha)),
+        None,
+        false,
+    );
+    assert_eq!(scoped.len(), 1);
+    assert_eq!(scoped[0].node.text, "Task 1 #task");
+
+    let open = vault.query_by_tag(
+        &["ops".to_string()],
+        None,
+        None,
+        Some(TaskState::Open),
+        false,
+    );
+    assert_eq!(open.len(), 1);
+
+    let results = vault
+        .query("#task AND NOT #decision", None, false, None, false)
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].file_path, alpha);
+    assert_eq!(results[0].node.text, "Task 1 #task");
+
+    // Inherited tags let children of a tagged item match.
+    let inherited = vault.query("#decision", None, true, None, false).unwrap();
+    assert!(inherited
+        .iter()
+        .any(|r| r.node.text == "This is a test note"));
+
+    let err = vault
+        .query("#task AND", None, false, None, false)
+        .unwrap_err();
+    match err {
+        LedgerError::Parse { offset, .. } => assert_eq!(offset, Some(9)),
+        other => panic!("expected a parse error, got {:?}", other),
+    }
+
+    let _ = fs::remove_dir_all(&temp);
+}
@@ -0,0 +1,56 @@
+//! Integration test: rename_tag rewrites and merges tags across vault files.
+
+use ledger_core::{open_vault_impl, read_file_impl, rename_tag_impl, write_file_impl, LedgerError};
+use std::fs;
+
+#[test]
+fn rename_and_merge_across_files() {
+    let temp = std::env::temp_dir().join("ledger_test_tag_rename");
+    let _ = fs::remove_dir_all(&temp);
+    fs::create_dir_all(&temp).unwrap();
+    let root = open_vault_impl(temp.to_str().unwrap()).unwrap().root_path;
+
+    write_file_impl(&root, "a.md", "- Flaky job #ci\n  - Retry #ci/retry\n").unwrap();
+    write_file_impl(&root, "b.md", "- Release #ci #build\n- Other #ops\n").unwrap();
+    write_file_impl(&root, "c.md", "- Nothing tagged\n").unwrap();
+    let paths: Vec<String> = ["a.md", "b.md", "c.md"].map(String::from).to_vec();
+
+    let report = rename_tag_impl(&root, &paths, "#ci", "build", true, true).unwrap();
+    assert!(report.dry_run);
+    assert_eq!((report.files_changed, report.nodes_changed), (2, 3));
+    assert_eq!(
+        read_file_impl(&root, "a.md").unwrap(),
+        "- Flaky job #ci\n  - Retry #ci/retry\n"
+    );
+
+    let report = rename_tag_impl(&root, &paths, "ci", "build", true, false).unwrap();
+    assert_eq!(report.files_changed, 2);
+    assert_eq!(
+        read_file_impl(&root, "a.md").unwrap(),
+        "- Flaky job #build\n  - Retry #build/retry\n"
+    );
+    assert_eq!(
+        read_file_impl(&root, "b.md").unwrap(),
+        "- Release #build\n- Other #ops\n"
+    );
+
+    // A file that cannot be read is reported and the others are still renamed.
+    let paths: Vec<String> = ["missing.md", "b.md"].map(String::from).to_vec();
+    let report = rename_tag_impl(&root, &paths, "ops", "infra", false, false).unwrap();
+    assert_eq!(report.files_changed, 1);
+    assert_eq!(report.files[0].file_path, "missing.md");
+    assert_eq!(
+        report.files[0].error.as_ref().map(LedgerError::code),
+        Some("NotFound")
+    );
+    assert!(report.files[1].error.is_none());
+    assert_eq!(
+        read_file_impl(&root, "b.md").unwrap(),
+        "- Release #build\n- Other #infra\n"
+    );
+
+    assert!(rename_tag_impl(&root, &paths, "ops", "bad tag", false, true).is_err());
+    assert!(rename_tag_impl(&root, &paths, "ops", "ops", false, true).is_err());
+
+    let _ = fs::remove_dir_all(&temp);
+}
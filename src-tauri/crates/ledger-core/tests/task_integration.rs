@@ -0,0 +1,39 @@
+//! Integration test: toggle_task flips a checkbox in the file on disk.
+
+use ledger_core::{open_vault_impl, read_file_impl, toggle_task_impl, write_file_impl, TaskState};
+use std::fs;
+
+#[test]
+fn toggle_task_round_trip() {
+    let temp = std::env::temp_dir().join("ledger_test_task");
+    let _ = fs::remove_dir_all(&temp);
+    fs::create_dir_all(&temp).unwrap();
+
+    let result = open_vault_impl(temp.to_str().unwrap()).unwrap();
+    let root = &result.root_path;
+
+    write_file_impl(root, "todo.md", "- [ ] Rotate keys #ops\n- Notes\n").unwrap();
+
+    assert_eq!(
+        toggle_task_impl(root, "todo.md", "0").unwrap(),
+        TaskState::Done
+    );
+    assert_eq!(
+        read_file_impl(root, "todo.md").unwrap(),
+        "- [x] Rotate keys #ops\n- Notes\n"
+    );
+    assert_eq!(
+        toggle_task_impl(root, "todo.md", "0").unwrap(),
+        TaskState::Open
+    );
+    assert_eq!(
+        read_file_impl(root, "todo.md").unwrap(),
+        "- [ ] Rotate keys #ops\n- Notes\n"
+    );
+
+    let err = toggle_task_impl(root, "todo.md", "1").unwrap_err();
+    assert_eq!(err.code(), "InvalidInput");
+    assert!(err.to_string().contains("not a task"));
+
+    let _ = fs::remove_dir_all(&temp);
+}
@@ -1,6 +1,6 @@
 //! Integration test: open_vault on a temp dir with two .md files returns root_path and file_paths length 2.
 
-use ledger_lib::open_vault_impl;
+use ledger_core::open_vault_impl;
 use std::fs;
 
 #[test]
@@ -19,3 +19,17 @@ fn open_vault_returns_root_and_two_md_paths() {
 
     let _ = fs::remove_dir_all(&temp);
 }
+
+#[test]
+fn open_vault_skips_ledger_dir() {
+    let temp = std::env::temp_dir().join("ledger_test_vault_ledger_dir");
+    let _ = fs::remove_dir_all(&temp);
+    fs::create_dir_all(temp.join(".ledger")).unwrap();
+    fs::File::create(temp.join("a.md")).unwrap();
+    fs::File::create(temp.join(".ledger").join("cached.md")).unwrap();
+
+    let result = open_vault_impl(temp.to_str().unwrap()).unwrap();
+    assert_eq!(result.file_paths, vec!["a.md".to_string()]);
+
+    let _ = fs::remove_dir_all(&temp);
+}
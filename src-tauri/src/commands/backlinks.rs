@@ -0,0 +1,18 @@
+//! "What links here" for a note or one of its items.
+
+use ledger_core::{LedgerError, QueryResultItem};
+use std::sync::Mutex;
+use tauri::State;
+
+use super::vault::{with_vault, VaultState};
+
+/// List items whose wikilinks point at the note at `path`, or with `node_id` at that
+/// item's `^block-id`.
+#[tauri::command]
+pub fn get_backlinks(
+    path: String,
+    node_id: Option<String>,
+    state: State<'_, Mutex<VaultState>>,
+) -> Result<Vec<QueryResultItem>, LedgerError> {
+    with_vault(&state, |v| v.backlinks(&path, node_id.as_deref()))
+}
@@ -0,0 +1,25 @@
+//! Edits to individual list items, written back to the file on disk.
+
+use ledger_core::{LedgerError, TaskState};
+use std::sync::Mutex;
+use tauri::State;
+
+use super::vault::{with_vault, VaultState};
+
+#[tauri::command]
+pub fn assign_block_id(
+    path: String,
+    node_id: String,
+    state: State<'_, Mutex<VaultState>>,
+) -> Result<String, LedgerError> {
+    with_vault(&state, |v| v.assign_block_id(&path, &node_id))
+}
+
+#[tauri::command]
+pub fn toggle_task(
+    path: String,
+    node_id: String,
+    state: State<'_, Mutex<VaultState>>,
+) -> Result<TaskState, LedgerError> {
+    with_vault(&state, |v| v.toggle_task(&path, &node_id))
+}
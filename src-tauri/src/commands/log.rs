@@ -1,11 +1,7 @@
 use tracing::{event, Level};
 
 #[tauri::command]
-pub fn log_from_frontend(
-    level: String,
-    message: String,
-    _payload: Option<serde_json::Value>,
-) {
+pub fn log_from_frontend(level: String, message: String, _payload: Option<serde_json::Value>) {
     let level = level.to_lowercase();
     match level.as_str() {
         "error" => event!(Level::ERROR, "{}", message),
@@ -1,10 +1,16 @@
+//! Tauri commands: thin wrappers that run `ledger_core` operations on the open vault.
+
+pub mod backlinks;
+pub mod edit;
 pub mod file;
 pub mod log;
 pub mod parse;
 pub mod query;
+pub mod replace;
 pub mod search;
 pub mod session;
+pub mod tags;
+pub mod trash;
 pub mod vault;
 
-pub use file::{create_file_impl, delete_file_impl, read_file_impl, write_file_impl};
-pub use vault::{open_vault_impl, VaultState};
+pub use vault::VaultState;
@@ -1,4 +1,4 @@
-use crate::markdown;
+use ledger_core::markdown;
 use serde::Serialize;
 
 #[derive(Serialize)]
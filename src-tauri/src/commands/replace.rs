@@ -0,0 +1,25 @@
+use ledger_core::{FileReplace, LedgerError, ReplaceSelection, SearchOptions};
+use std::sync::Mutex;
+use tauri::State;
+
+use super::vault::{with_vault, VaultState};
+
+#[tauri::command]
+pub fn replace_in_vault(
+    query: String,
+    replacement: String,
+    options: Option<SearchOptions>,
+    paths: Option<Vec<String>>,
+    selection: Option<Vec<ReplaceSelection>>,
+    state: State<'_, Mutex<VaultState>>,
+) -> Result<Vec<FileReplace>, LedgerError> {
+    with_vault(&state, |v| {
+        v.replace(
+            &query,
+            &replacement,
+            options.unwrap_or_default(),
+            paths.as_deref(),
+            selection.as_deref(),
+        )
+    })
+}
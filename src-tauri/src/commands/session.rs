@@ -1,6 +1,7 @@
+use ledger_core::LedgerError;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Manager};
 
 const SESSION_FILENAME: &str = "config.json";
@@ -10,37 +11,55 @@ pub struct SessionData {
     pub last_vault_path: Option<String>,
     pub last_file_path: Option<String>,
     pub theme: Option<String>,
+    /// Trash entries older than this many days are purged when a vault is opened.
+    /// None keeps them until purged by hand.
+    pub trash_retention_days: Option<u64>,
 }
 
-fn session_path(app: &AppHandle) -> Result<PathBuf, String> {
+fn session_path(app: &AppHandle) -> Result<PathBuf, LedgerError> {
     let dir = app
         .path()
         .app_data_dir()
-        .map_err(|e: tauri::Error| e.to_string())?;
-    fs::create_dir_all(&dir).map_err(|e: std::io::Error| e.to_string())?;
+        .map_err(|e: tauri::Error| LedgerError::Io {
+            message: e.to_string(),
+        })?;
+    fs::create_dir_all(&dir).map_err(|e| io_error(&dir, e))?;
     Ok(dir.join(SESSION_FILENAME))
 }
 
+fn io_error(path: &Path, e: std::io::Error) -> LedgerError {
+    LedgerError::io(&path.to_string_lossy(), e)
+}
+
+/// Saved session, or defaults if there is none or it cannot be read.
+pub(crate) fn load_session(app: &AppHandle) -> SessionData {
+    get_session(app.clone()).unwrap_or_default()
+}
+
 #[tauri::command]
-pub fn get_session(app: AppHandle) -> Result<SessionData, String> {
+pub fn get_session(app: AppHandle) -> Result<SessionData, LedgerError> {
     let path = session_path(&app)?;
     if !path.exists() {
         return Ok(SessionData::default());
     }
-    let s = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-    serde_json::from_str(&s).map_err(|e| e.to_string())
+    let s = fs::read_to_string(&path).map_err(|e| io_error(&path, e))?;
+    Ok(serde_json::from_str(&s)?)
 }
 
+/// Saves the given values and keeps the others. `clear_trash_retention` resets
+/// `trash_retention_days` to None, which a missing argument cannot express.
 #[tauri::command]
 pub fn save_session(
     app: AppHandle,
     last_vault_path: Option<String>,
     last_file_path: Option<String>,
     theme: Option<String>,
-) -> Result<(), String> {
+    trash_retention_days: Option<u64>,
+    clear_trash_retention: Option<bool>,
+) -> Result<(), LedgerError> {
     let path = session_path(&app)?;
     let mut current = if path.exists() {
-        let s = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        let s = fs::read_to_string(&path).map_err(|e| io_error(&path, e))?;
         serde_json::from_str(&s).unwrap_or_default()
     } else {
         SessionData::default()
@@ -54,6 +73,12 @@ pub fn save_session(
     if theme.is_some() {
         current.theme = theme;
     }
-    let s = serde_json::to_string_pretty(&current).map_err(|e| e.to_string())?;
-    fs::write(&path, s).map_err(|e| e.to_string())
+    if trash_retention_days.is_some() {
+        current.trash_retention_days = trash_retention_days;
+    }
+    if clear_trash_retention == Some(true) {
+        current.trash_retention_days = None;
+    }
+    let s = serde_json::to_string_pretty(&current)?;
+    fs::write(&path, s).map_err(|e| io_error(&path, e))
 }
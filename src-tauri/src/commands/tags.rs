@@ -0,0 +1,28 @@
+use ledger_core::{LedgerError, TagInfo, TagRenameReport};
+use std::sync::Mutex;
+use tauri::State;
+
+use super::vault::{with_vault, VaultState};
+
+#[tauri::command]
+pub fn list_tags(state: State<'_, Mutex<VaultState>>) -> Result<Vec<TagInfo>, LedgerError> {
+    with_vault(&state, |v| Ok(v.list_tags()))
+}
+
+#[tauri::command]
+pub fn rename_tag(
+    from: String,
+    to: String,
+    include_children: Option<bool>,
+    dry_run: Option<bool>,
+    state: State<'_, Mutex<VaultState>>,
+) -> Result<TagRenameReport, LedgerError> {
+    with_vault(&state, |v| {
+        v.rename_tag(
+            &from,
+            &to,
+            include_children.unwrap_or(false),
+            dry_run.unwrap_or(false),
+        )
+    })
+}
@@ -0,0 +1,31 @@
+use ledger_core::{LedgerError, RestoreConflict, TrashEntry};
+use std::sync::Mutex;
+use tauri::State;
+
+use super::vault::{with_vault, VaultState};
+
+#[tauri::command]
+pub fn list_trash(state: State<'_, Mutex<VaultState>>) -> Result<Vec<TrashEntry>, LedgerError> {
+    with_vault(&state, |v| v.list_trash())
+}
+
+/// Restore a trash entry and add its notes back to the vault. Returns the restored path.
+#[tauri::command]
+pub fn restore_from_trash(
+    id: String,
+    on_conflict: Option<RestoreConflict>,
+    state: State<'_, Mutex<VaultState>>,
+) -> Result<String, LedgerError> {
+    with_vault(&state, |v| {
+        v.restore_from_trash(&id, on_conflict.unwrap_or_default())
+    })
+}
+
+#[tauri::command]
+pub fn purge_trash(
+    ids: Option<Vec<String>>,
+    older_than_days: Option<u64>,
+    state: State<'_, Mutex<VaultState>>,
+) -> Result<Vec<String>, LedgerError> {
+    with_vault(&state, |v| v.purge_trash(ids.as_deref(), older_than_days))
+}
@@ -1,72 +1,62 @@
-use serde::Serialize;
-use std::path::Path;
-use std::sync::Mutex;
-use tauri::State;
+use ledger_core::{LedgerError, OpenVaultResult, Vault};
+use std::sync::{Mutex, MutexGuard};
+use tauri::{AppHandle, State};
 
 #[derive(Default)]
 pub struct VaultState {
-    pub root_path: Option<String>,
-    pub file_paths: Vec<String>,
+    pub vault: Option<Vault>,
+    /// Filesystem watcher for the open vault; dropping it stops watching.
+    pub watcher: Option<notify::RecommendedWatcher>,
 }
 
-#[derive(Serialize)]
-pub struct OpenVaultResult {
-    pub root_path: String,
-    pub file_paths: Vec<String>,
-}
-
-/// Collects all .md file paths under dir (relative to root). Used by open_vault and tests.
-pub fn collect_md_paths(dir: &Path, root: &Path, out: &mut Vec<String>) -> std::io::Result<()> {
-    if !dir.is_dir() {
-        return Ok(());
-    }
-    for entry in std::fs::read_dir(dir)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_dir() {
-            collect_md_paths(&path, root, out)?;
-        } else if path.extension().map_or(false, |e| e == "md") {
-            if let Ok(rel) = path.strip_prefix(root) {
-                out.push(rel.to_string_lossy().into_owned());
-            } else {
-                out.push(path.to_string_lossy().into_owned());
-            }
-        }
-    }
-    Ok(())
+/// Lock the vault state. A poisoned lock (a command panicked) is reported as `Io`.
+fn lock(state: &Mutex<VaultState>) -> Result<MutexGuard<'_, VaultState>, LedgerError> {
+    state.lock().map_err(|e| LedgerError::Io {
+        message: e.to_string(),
+    })
 }
 
-/// Core vault-open logic (no state). Used by open_vault command and integration tests.
-pub fn open_vault_impl(path: &str) -> Result<OpenVaultResult, String> {
-    let root = Path::new(path);
-    if !root.exists() {
-        return Err(format!("Path does not exist: {}", path));
-    }
-    if !root.is_dir() {
-        return Err(format!("Path is not a directory: {}", path));
-    }
-    let root_path_canon = std::fs::canonicalize(root).map_err(|e| e.to_string())?;
-    let root_path = root_path_canon.to_string_lossy().into_owned();
-
-    let mut file_paths = Vec::new();
-    collect_md_paths(Path::new(&root_path), Path::new(&root_path), &mut file_paths)
-        .map_err(|e| e.to_string())?;
-
-    file_paths.sort();
-
-    Ok(OpenVaultResult {
-        root_path,
-        file_paths,
-    })
+/// Run `f` on the open vault, or fail with `LedgerError::NoVault`.
+pub(crate) fn with_vault<T>(
+    state: &Mutex<VaultState>,
+    f: impl FnOnce(&mut Vault) -> Result<T, LedgerError>,
+) -> Result<T, LedgerError> {
+    let mut s = lock(state)?;
+    let vault = s.vault.as_mut().ok_or(LedgerError::NoVault)?;
+    f(vault)
 }
 
 #[tauri::command]
-pub fn open_vault(path: String, state: State<'_, Mutex<VaultState>>) -> Result<OpenVaultResult, String> {
-    let result = open_vault_impl(&path)?;
-    {
-        let mut s = state.lock().map_err(|e| e.to_string())?;
-        s.root_path = Some(result.root_path.clone());
-        s.file_paths = result.file_paths.clone();
+pub fn open_vault(
+    path: String,
+    app: AppHandle,
+    state: State<'_, Mutex<VaultState>>,
+) -> Result<OpenVaultResult, LedgerError> {
+    let vault = Vault::open(&path)?;
+    if let Some(days) = super::session::load_session(&app).trash_retention_days {
+        if let Err(e) = vault.purge_trash(None, Some(days)) {
+            tracing::warn!("Could not purge old trash entries: {}", e);
+        }
     }
+    let result = OpenVaultResult {
+        root_path: vault.root_path.clone(),
+        file_paths: vault.file_paths.clone(),
+    };
+    // The watcher is started without the lock held, since its callback takes the lock.
+    // Events that arrive before the state is updated are ignored (root mismatch).
+    let watcher = match crate::watcher::start(app, result.root_path.clone().into()) {
+        Ok(w) => Some(w),
+        Err(e) => {
+            tracing::warn!("Could not watch vault {}: {}", result.root_path, e);
+            None
+        }
+    };
+    let previous = {
+        let mut s = lock(&state)?;
+        s.vault = Some(vault);
+        std::mem::replace(&mut s.watcher, watcher)
+    };
+    // Stop the previous vault's watcher outside the lock.
+    drop(previous);
     Ok(result)
 }
@@ -4,14 +4,13 @@
 )]
 
 mod commands;
-mod markdown;
+mod watcher;
 
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
 use commands::VaultState;
 use std::sync::Mutex;
-
-pub use commands::{create_file_impl, delete_file_impl, read_file_impl, write_file_impl, open_vault_impl};
+use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -32,11 +31,38 @@ pub fn run() {
             commands::file::write_file,
             commands::file::create_file,
             commands::file::delete_file,
+            commands::file::move_file,
+            commands::file::rename_file,
+            commands::file::create_folder,
+            commands::file::rename_folder,
+            commands::file::delete_folder,
+            commands::file::list_tree,
+            commands::trash::list_trash,
+            commands::trash::restore_from_trash,
+            commands::trash::purge_trash,
             commands::parse::parse_file,
+            commands::edit::assign_block_id,
+            commands::edit::toggle_task,
             commands::query::query_by_tag,
+            commands::query::query_nodes,
+            commands::backlinks::get_backlinks,
+            commands::replace::replace_in_vault,
             commands::search::search_full_text,
+            commands::tags::list_tags,
+            commands::tags::rename_tag,
             commands::log::log_from_frontend,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            // Index saves are batched; write what is left before the process ends.
+            if let tauri::RunEvent::Exit = event {
+                let state = app.state::<Mutex<VaultState>>();
+                if let Ok(mut s) = state.lock() {
+                    if let Some(vault) = s.vault.as_mut() {
+                        vault.flush_index();
+                    }
+                };
+            }
+        });
 }
@@ -0,0 +1,290 @@
+//! Filesystem watcher started by `open_vault`. Keeps the vault's `file_paths` and the
+//! vault index in sync with changes made outside the app (git, sync tools, other
+//! editors) and tells the frontend through Tauri events:
+//! `vault://file-added`, `vault://file-changed`, `vault://file-removed` (payload
+//! `{ path }`) and `vault://file-renamed` (payload `{ from, to }`).
+
+use crate::commands::vault::VaultState;
+use ledger_core::index::LEDGER_DIR;
+use ledger_core::vault::{collect_md_paths, Vault};
+use notify::event::{ModifyKind, RenameMode};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum VaultEvent {
+    Added { path: String },
+    Changed { path: String },
+    Removed { path: String },
+    Renamed { from: String, to: String },
+}
+
+impl VaultEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            VaultEvent::Added { .. } => "vault://file-added",
+            VaultEvent::Changed { .. } => "vault://file-changed",
+            VaultEvent::Removed { .. } => "vault://file-removed",
+            VaultEvent::Renamed { .. } => "vault://file-renamed",
+        }
+    }
+}
+
+/// Vault-relative path for `path`, or None if it is outside the vault or in `.ledger/`.
+fn relative(root: &Path, path: &Path) -> Option<PathBuf> {
+    let rel = path.strip_prefix(root).ok()?;
+    match rel.components().next() {
+        Some(first) if first.as_os_str() != LEDGER_DIR => Some(rel.to_path_buf()),
+        _ => None,
+    }
+}
+
+fn is_md(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "md")
+}
+
+/// Forget `rel` and, if it was a folder, every known file under it. Returns the removed
+/// paths.
+fn remove_known(state: &mut Vault, rel: &Path) -> Vec<String> {
+    let removed: Vec<String> = state
+        .file_paths
+        .iter()
+        .filter(|p| Path::new(p.as_str()).starts_with(rel))
+        .cloned()
+        .collect();
+    for p in &removed {
+        state.forget_file(p);
+    }
+    state.file_paths.retain(|p| !removed.contains(p));
+    removed
+}
+
+/// Reconcile one path with the disk: new files are added, known files re-indexed,
+/// missing files removed. Folders are scanned for notes.
+fn sync_path(state: &mut Vault, root: &Path, path: &Path) -> Vec<VaultEvent> {
+    let Some(rel) = relative(root, path) else {
+        return Vec::new();
+    };
+    let mut found = Vec::new();
+    if path.is_dir() {
+        let _ = collect_md_paths(path, root, &mut found);
+    } else if path.is_file() && is_md(path) {
+        found.push(rel.to_string_lossy().into_owned());
+    } else {
+        return remove_known(state, &rel)
+            .into_iter()
+            .map(|path| VaultEvent::Removed { path })
+            .collect();
+    }
+
+    let mut events = Vec::new();
+    for p in found {
+        if state.file_paths.contains(&p) {
+            if state.refresh_index(std::slice::from_ref(&p)) {
+                events.push(VaultEvent::Changed { path: p });
+            }
+        } else {
+            state.reindex_file(&p);
+            state.file_paths.push(p.clone());
+            events.push(VaultEvent::Added { path: p });
+        }
+    }
+    state.file_paths.sort();
+    events
+}
+
+/// Apply one filesystem event to the vault state and return the events to emit.
+/// `rename` is set when `paths` is a `[from, to]` pair.
+pub fn apply_change(
+    state: &mut Vault,
+    root: &Path,
+    paths: &[PathBuf],
+    rename: bool,
+) -> Vec<VaultEvent> {
+    if rename && paths.len() == 2 {
+        let (from, to) = (&paths[0], &paths[1]);
+        let removed = match relative(root, from) {
+            Some(rel) => remove_known(state, &rel),
+            None => Vec::new(),
+        };
+        let mut added = sync_path(state, root, to);
+        let mut events = Vec::new();
+        if let (Some(from_rel), Some(to_rel)) = (relative(root, from), relative(root, to)) {
+            for old in removed {
+                // A renamed file has an empty suffix; joining "" would add a separator.
+                let new = match Path::new(&old).strip_prefix(&from_rel) {
+                    Ok(suffix) if !suffix.as_os_str().is_empty() => to_rel.join(suffix),
+                    _ => to_rel.clone(),
+                };
+                let new = new.to_string_lossy().into_owned();
+                let pos = added
+                    .iter()
+                    .position(|e| *e == VaultEvent::Added { path: new.clone() });
+                match pos {
+                    Some(i) => {
+                        added.remove(i);
+                        events.push(VaultEvent::Renamed { from: old, to: new });
+                    }
+                    None => events.push(VaultEvent::Removed { path: old }),
+                }
+            }
+        } else {
+            events.extend(removed.into_iter().map(|path| VaultEvent::Removed { path }));
+        }
+        events.extend(added);
+        return events;
+    }
+
+    paths
+        .iter()
+        .flat_map(|p| sync_path(state, root, p))
+        .collect()
+}
+
+fn handle_event(app: &AppHandle, root: &Path, event: notify::Event) {
+    if matches!(event.kind, EventKind::Access(_)) {
+        return;
+    }
+    let rename = matches!(
+        event.kind,
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both))
+    );
+    let events = {
+        let state = app.state::<Mutex<VaultState>>();
+        let mut s = match state.lock() {
+            Ok(s) => s,
+            Err(e) => {
+                tracing::warn!("Vault watcher could not lock state: {}", e);
+                return;
+            }
+        };
+        // Ignore late events from a vault that has since been closed or replaced.
+        match s.vault.as_mut() {
+            Some(vault) if vault.root() == root => apply_change(vault, root, &event.paths, rename),
+            _ => return,
+        }
+    };
+    for e in events {
+        if let Err(err) = app.emit(e.name(), &e) {
+            tracing::warn!("Could not emit {}: {}", e.name(), err);
+        }
+    }
+}
+
+/// Watch `root` recursively. Dropping the returned watcher stops it.
+pub fn start(app: AppHandle, root: PathBuf) -> notify::Result<RecommendedWatcher> {
+    let handler_root = root.clone();
+    let mut watcher =
+        notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+            Ok(event) => handle_event(&app, &handler_root, event),
+            Err(e) => tracing::warn!("Vault watcher error: {}", e),
+        })?;
+    watcher.watch(&root, RecursiveMode::Recursive)?;
+    Ok(watcher)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn vault(name: &str) -> (PathBuf, Vault) {
+        let root = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("notes")).unwrap();
+        fs::write(root.join("notes/a.md"), "- a").unwrap();
+        let vault = Vault::open(root.to_str().unwrap()).unwrap();
+        (vault.root().to_path_buf(), vault)
+    }
+
+    fn rel(parts: &[&str]) -> String {
+        parts
+            .iter()
+            .collect::<PathBuf>()
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn added_changed_removed() {
+        let (root, mut state) = vault("ledger_test_watch_basic");
+
+        fs::write(root.join("b.md"), "- b").unwrap();
+        let events = apply_change(&mut state, &root, &[root.join("b.md")], false);
+        assert_eq!(
+            events,
+            vec![VaultEvent::Added {
+                path: "b.md".into()
+            }]
+        );
+        assert!(state.file_paths.contains(&"b.md".to_string()));
+
+        // Unchanged content is not reported again.
+        assert!(apply_change(&mut state, &root, &[root.join("b.md")], false).is_empty());
+        fs::write(root.join("b.md"), "- b #changed").unwrap();
+        let events = apply_change(&mut state, &root, &[root.join("b.md")], false);
+        assert_eq!(
+            events,
+            vec![VaultEvent::Changed {
+                path: "b.md".into()
+            }]
+        );
+        assert_eq!(
+            state.index.get("b.md").unwrap().nodes[0].tags,
+            vec!["changed"]
+        );
+
+        fs::remove_dir_all(root.join("notes")).unwrap();
+        let events = apply_change(&mut state, &root, &[root.join("notes")], false);
+        assert_eq!(
+            events,
+            vec![VaultEvent::Removed {
+                path: rel(&["notes", "a.md"])
+            }]
+        );
+        assert_eq!(state.file_paths, vec!["b.md".to_string()]);
+
+        // Non-notes and the app's own directory are ignored.
+        fs::write(root.join("c.txt"), "x").unwrap();
+        fs::create_dir_all(root.join(".ledger")).unwrap();
+        fs::write(root.join(".ledger/x.md"), "x").unwrap();
+        let paths = [root.join("c.txt"), root.join(".ledger/x.md")];
+        assert!(apply_change(&mut state, &root, &paths, false).is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn renames_files_and_folders() {
+        let (root, mut state) = vault("ledger_test_watch_rename");
+
+        fs::rename(root.join("notes/a.md"), root.join("notes/z.md")).unwrap();
+        let paths = [root.join("notes/a.md"), root.join("notes/z.md")];
+        let events = apply_change(&mut state, &root, &paths, true);
+        assert_eq!(
+            events,
+            vec![VaultEvent::Renamed {
+                from: rel(&["notes", "a.md"]),
+                to: rel(&["notes", "z.md"]),
+            }]
+        );
+
+        fs::rename(root.join("notes"), root.join("archive")).unwrap();
+        let paths = [root.join("notes"), root.join("archive")];
+        let events = apply_change(&mut state, &root, &paths, true);
+        assert_eq!(
+            events,
+            vec![VaultEvent::Renamed {
+                from: rel(&["notes", "z.md"]),
+                to: rel(&["archive", "z.md"]),
+            }]
+        );
+        assert_eq!(state.file_paths, vec![rel(&["archive", "z.md"])]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -1,4 +0,0 @@
-//! Integration tests for vault, session, and file CRUD.
-
-mod test_file_crud;
-mod test_vault;
@@ -1,58 +0,0 @@
-//! Integration test: query_by_tag with basic tags and scope filtering.
-
-use ledger_lib::{
-    create_file_impl, open_vault_impl, write_file_impl, commands::query::query_by_tag,
-};
-use std::fs;
-use std::sync::Mutex;
-use tauri::State;
-
-#[test]
-fn query_by_tag_finds_items_with_tag() {
-    let temp = std::env::temp_dir().join("ledger_test_query");
-    let _ = fs::remove_dir_all(&temp);
-    fs::create_dir_all(&temp).unwrap();
-
-    let result = open_vault_impl(temp.to_str().unwrap()).unwrap();
-    let root = &result.root_path;
-
-    // Create two files with items tagged #decision
-    create_file_impl(root, "f1.md").unwrap();
-    write_file_impl(root, "f1.md", "- Item 1 #decision\n- Item 2").unwrap();
-
-    create_file_impl(root, "f2.md").unwrap();
-    write_file_impl(root, "f2.md", "- Task A #decision\n- Task B").unwrap();
-
-    // Note: query_by_tag requires VaultState which is only available during Tauri runtime.
-    // For now, this test demonstrates the expected structure.
-    // Integration with VaultState should be tested in end-to-end or when Tauri runtime is available.
-    
-    // Expected: query for #decision should return items from both files with "Item 1" and "Task A"
-    // Results should include file_path, parent_path (None for root items), and node with tags.
-
-    let _ = fs::remove_dir_all(&temp);
-}
-
-#[test]
-fn query_by_tag_scope_filter() {
-    let temp = std::env::temp_dir().join("ledger_test_query_scope");
-    let _ = fs::remove_dir_all(&temp);
-    fs::create_dir_all(&temp).unwrap();
-
-    let result = open_vault_impl(temp.to_str().unwrap()).unwrap();
-    let root = &result.root_path;
-
-    // Create a file with hierarchical items, some tagged
-    create_file_impl(root, "project.md").unwrap();
-    write_file_impl(
-        root,
-        "project.md",
-        "- Project A\n  - Task 1 #task\n  - Task 2\n- Project B\n  - Task 3 #task",
-    ).unwrap();
-
-    // Expected: query for #task with scope restricted to "Project A" (node id 0)
-    // should return only "Task 1", not "Task 3" (which is under Project B).
-    // This verifies the scope_node_id filtering works correctly.
-
-    let _ = fs::remove_dir_all(&temp);
-}
@@ -94,7 +94,7 @@ const AppShell: React.FC = () => {
       await tauriClient.writeFile(state.selectedPath, state.editorContent);
     } catch (err) {
       console.error(err);
-      alert(String(err));
+      alert(tauriClient.errorMessage(err));
     }
   };
 
@@ -133,7 +133,7 @@ const AppShell: React.FC = () => {
       await tauriClient.saveSession({ last_vault_path: result.root_path });
     } catch (err) {
       console.error(err);
-      alert(String(err));
+      alert(tauriClient.errorMessage(err));
     }
   };
 
@@ -52,7 +52,7 @@ const Editor: React.FC<EditorProps> = ({
       dispatch({ type: "SET_FILE_PATHS", payload: paths ?? [] });
     } catch (err) {
       console.error(err);
-      alert(String(err));
+      alert(tauriClient.errorMessage(err));
     }
   };
 
@@ -37,7 +37,7 @@ const FileTree: React.FC<FileTreeProps> = ({
       await onSelectFile(path);
     } catch (err) {
       console.error(err);
-      alert(String(err));
+      alert(tauriClient.errorMessage(err));
     }
   };
 
@@ -49,7 +49,7 @@ const FileTree: React.FC<FileTreeProps> = ({
       await onRefreshList();
     } catch (err) {
       console.error(err);
-      alert(String(err));
+      alert(tauriClient.errorMessage(err));
     }
   };
 
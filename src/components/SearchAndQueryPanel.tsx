@@ -36,7 +36,7 @@ const SearchAndQueryPanel: React.FC<SearchAndQueryPanelProps> = ({
       });
     } catch (err) {
       console.error(err);
-      alert(String(err));
+      alert(tauriClient.errorMessage(err));
     }
   };
 
@@ -54,7 +54,7 @@ const SearchAndQueryPanel: React.FC<SearchAndQueryPanelProps> = ({
       });
     } catch (err) {
       console.error(err);
-      alert(String(err));
+      alert(tauriClient.errorMessage(err));
     }
   };
 
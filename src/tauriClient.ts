@@ -1,6 +1,41 @@
 import { invoke } from "@tauri-apps/api/core";
 import { open } from "@tauri-apps/plugin-dialog";
 
+/**
+ * Position in a source file: byte offset, 0-based line, and 0-based
+ * column counted in characters.
+ */
+export type Position = {
+  offset: number;
+  line: number;
+  column: number;
+};
+
+/**
+ * Half-open source range [start, end).
+ */
+export type SourceSpan = {
+  start: Position;
+  end: Position;
+};
+
+/**
+ * Checkbox state of a task list item.
+ */
+export type TaskState = "open" | "done" | "cancelled";
+
+/**
+ * A `[[wikilink]]` in item text: `[[note]]`, `[[note#heading]]`,
+ * `[[note^block-id]]`, optionally `|shown text`. `target` is empty for links into
+ * the same file.
+ */
+export type WikiLink = {
+  target: string;
+  heading: string | null;
+  block_id: string | null;
+  alias: string | null;
+};
+
 /**
  * TreeNode represents a single list item in the parsed hierarchy.
  * Fields match the contract in tauri-commands.md.
@@ -12,6 +47,14 @@ export type TreeNode = {
   tags: string[];
   parent_id: string | number | null;
   children_ids: (string | number)[];
+  span?: SourceSpan;
+  text_span?: SourceSpan;
+  block_id?: string | null;
+  stable_id?: string;
+  task?: TaskState | null;
+  task_span?: SourceSpan | null;
+  attributes?: Record<string, string>;
+  links?: WikiLink[];
 };
 
 /**
@@ -21,6 +64,28 @@ export type QueryResultItem = {
   file_path: string;
   parent_path: string | null;
   node: TreeNode;
+  own_tags?: string[];
+  effective_tags?: string[];
+};
+
+/**
+ * Options for plain (non-fuzzy) full-text search. All default to false.
+ */
+export type SearchOptions = {
+  case_sensitive?: boolean;
+  whole_word?: boolean;
+  regex?: boolean;
+};
+
+/**
+ * Range [start, end) of one occurrence in a line, as UTF-8 byte offsets and
+ * as UTF-16 code units (use the latter with JavaScript strings).
+ */
+export type MatchRange = {
+  start: number;
+  end: number;
+  start_utf16: number;
+  end_utf16: number;
 };
 
 /**
@@ -28,9 +93,83 @@ export type QueryResultItem = {
  */
 export type SearchMatch = {
   file_path: string;
+  /** 0-based line number in the file. */
+  line?: number;
   snippet_or_line: string;
   start_offset?: number;
   end_offset?: number;
+  start_offset_utf16?: number;
+  end_offset_utf16?: number;
+  /** Every occurrence in the line (plain search only). */
+  matches?: MatchRange[];
+  /** Fuzzy score, higher is better (fuzzy search only). */
+  score?: number | null;
+  /** Byte offset in the line of each matched character (fuzzy search only). */
+  positions?: number[] | null;
+  positions_utf16?: number[] | null;
+};
+
+/**
+ * One change in a replace_in_vault preview. Offsets are relative to the line.
+ */
+export type ReplaceChange = {
+  id: number;
+  line: number;
+  start: number;
+  end: number;
+  start_utf16: number;
+  end_utf16: number;
+  matched: string;
+  replacement: string;
+  line_text: string;
+  preview: string;
+};
+
+/**
+ * One file in a replace_in_vault preview or result.
+ */
+export type FileReplace = {
+  file_path: string;
+  version: string;
+  changes: ReplaceChange[];
+  applied: boolean;
+  error: LedgerError | null;
+};
+
+/**
+ * Changes to apply in one file. Omit change_ids to apply all of them.
+ */
+export type ReplaceSelection = {
+  file_path: string;
+  version: string;
+  change_ids?: number[];
+};
+
+/**
+ * A tag in the vault with usage counts, from list_tags.
+ */
+export type TagInfo = {
+  name: string;
+  node_count: number;
+  file_count: number;
+  first_seen_ms: number;
+  last_seen_ms: number;
+  co_occurring: { name: string; count: number }[];
+};
+
+/**
+ * Result of rename_tag: how many files and list items changed.
+ */
+export type TagRenameReport = {
+  dry_run: boolean;
+  files_changed: number;
+  nodes_changed: number;
+  files: {
+    file_path: string;
+    nodes_changed: number;
+    occurrences: number;
+    error: LedgerError | null;
+  }[];
 };
 
 /**
@@ -40,6 +179,8 @@ export type SessionData = {
   last_vault_path?: string;
   last_file_path?: string;
   theme?: string;
+  /** Purge trash entries older than this many days when a vault is opened. */
+  trash_retention_days?: number;
 };
 
 /**
@@ -59,13 +200,33 @@ export async function openVault(path: string): Promise<VaultInfo> {
   return invoke<VaultInfo>("open_vault", { path });
 }
 
+/**
+ * Events emitted by the backend's vault watcher. Paths are vault-relative.
+ * Subscribe with `listen` from "@tauri-apps/api/event".
+ */
+export const VAULT_EVENTS = {
+  fileAdded: "vault://file-added",
+  fileChanged: "vault://file-changed",
+  fileRemoved: "vault://file-removed",
+  fileRenamed: "vault://file-renamed",
+} as const;
+
+export type VaultFileEvent = { path: string };
+export type VaultRenameEvent = { from: string; to: string };
+
 export async function getSession(): Promise<SessionData> {
   return invoke<SessionData>("get_session");
 }
 
-export async function saveSession(
-  data: Partial<SessionData>
-): Promise<void> {
+/**
+ * Values to save; omitted ones are kept. Set clear_trash_retention to unset
+ * trash_retention_days.
+ */
+export type SaveSessionArgs = Partial<SessionData> & {
+  clear_trash_retention?: boolean;
+};
+
+export async function saveSession(data: SaveSessionArgs): Promise<void> {
   return invoke<void>("save_session", data);
 }
 
@@ -73,20 +234,211 @@ export async function listFiles(): Promise<string[]> {
   return invoke<string[]>("list_files");
 }
 
+/**
+ * File content and the version it was read at (see writeFile).
+ */
+export type FileContent = {
+  content: string;
+  version: string;
+};
+
+/**
+ * Stable code of a LedgerError.
+ */
+export type LedgerErrorCode =
+  | "NoVault"
+  | "NotFound"
+  | "OutsideVault"
+  | "InvalidPath"
+  | "AlreadyExists"
+  | "Conflict"
+  | "NodeNotFound"
+  | "TrashEntryNotFound"
+  | "DiskFull"
+  | "ReadOnly"
+  | "Parse"
+  | "InvalidInput"
+  | "Io";
+
+/**
+ * Rejection from every command. Branch on `code`; `message` is for
+ * display only. Which other fields are set depends on the code (see
+ * tauri-commands.md).
+ */
+export type LedgerError = {
+  code: LedgerErrorCode;
+  message: string;
+  path?: string;
+  reason?: string;
+  version?: string | null;
+  content?: string | null;
+  node_id?: string;
+  id?: string;
+  offset?: number | null;
+};
+
+export function isLedgerError(err: unknown): err is LedgerError {
+  return (
+    typeof err === "object" &&
+    err !== null &&
+    typeof (err as { code?: unknown }).code === "string" &&
+    typeof (err as { message?: unknown }).message === "string"
+  );
+}
+
+/**
+ * Text to show the user for any caught error.
+ */
+export function errorMessage(err: unknown): string {
+  return isLedgerError(err) ? err.message : String(err);
+}
+
+/**
+ * Rejection from writeFile when the file changed on disk since
+ * `expectedVersion`. `content` is null if the file was deleted.
+ */
+export type VersionConflict = LedgerError & {
+  code: "Conflict";
+  path: string;
+  version: string | null;
+  content: string | null;
+};
+
+export function isVersionConflict(err: unknown): err is VersionConflict {
+  return isLedgerError(err) && err.code === "Conflict";
+}
+
+export async function readFileVersioned(path: string): Promise<FileContent> {
+  return invoke<FileContent>("read_file", { path });
+}
+
 export async function readFile(path: string): Promise<string> {
-  return invoke<string>("read_file", { path });
+  return (await readFileVersioned(path)).content;
 }
 
-export async function writeFile(path: string, content: string): Promise<void> {
-  return invoke<void>("write_file", { path, content });
+/**
+ * Returns the new version. With `expectedVersion`, rejects with a
+ * VersionConflict instead of overwriting changes made elsewhere.
+ */
+export async function writeFile(
+  path: string,
+  content: string,
+  expectedVersion?: string
+): Promise<string> {
+  return invoke<string>("write_file", {
+    path,
+    content,
+    expected_version: expectedVersion,
+  });
 }
 
 export async function createFile(path: string): Promise<void> {
   return invoke<void>("create_file", { path });
 }
 
-export async function deleteFile(path: string): Promise<void> {
-  return invoke<void>("delete_file", { path });
+/**
+ * A deleted file or folder kept in the vault trash.
+ */
+export type TrashEntry = {
+  id: string;
+  original_path: string;
+  deleted_ms: number;
+  is_folder: boolean;
+};
+
+/** Moves the file to the vault trash. */
+export async function deleteFile(path: string): Promise<TrashEntry> {
+  return invoke<TrashEntry>("delete_file", { path });
+}
+
+export async function listTrash(): Promise<TrashEntry[]> {
+  return invoke<TrashEntry[]>("list_trash");
+}
+
+/** Returns the path the entry was restored to. */
+export async function restoreFromTrash(
+  id: string,
+  onConflict?: "fail" | "keep_both"
+): Promise<string> {
+  return invoke<string>("restore_from_trash", { id, on_conflict: onConflict });
+}
+
+/** Returns the ids that were permanently deleted. */
+export async function purgeTrash(
+  ids?: string[],
+  olderThanDays?: number
+): Promise<string[]> {
+  return invoke<string[]>("purge_trash", {
+    ids,
+    older_than_days: olderThanDays,
+  });
+}
+
+/**
+ * Result of moveFile / renameFile.
+ */
+export type MoveResult = {
+  from: string;
+  to: string;
+  links_updated: string[];
+  link_errors: { file_path: string; error: LedgerError }[];
+};
+
+export async function moveFile(
+  from: string,
+  to: string,
+  updateLinks?: boolean
+): Promise<MoveResult> {
+  return invoke<MoveResult>("move_file", {
+    from,
+    to,
+    update_links: updateLinks,
+  });
+}
+
+/**
+ * A folder or note from listTree. Folders first, then files, by name.
+ */
+export type TreeEntry = {
+  name: string;
+  path: string;
+  kind: "folder" | "file";
+  size: number | null;
+  modified_ms: number | null;
+  children: TreeEntry[];
+};
+
+export async function createFolder(path: string): Promise<void> {
+  return invoke<void>("create_folder", { path });
+}
+
+/** Returns the new paths of the notes that moved. */
+export async function renameFolder(
+  from: string,
+  to: string
+): Promise<string[]> {
+  return invoke<string[]>("rename_folder", { from, to });
+}
+
+/** Returns the paths of the notes that were removed. */
+export async function deleteFolder(path: string): Promise<string[]> {
+  return invoke<string[]>("delete_folder", { path });
+}
+
+export async function listTree(): Promise<TreeEntry[]> {
+  return invoke<TreeEntry[]>("list_tree");
+}
+
+export async function renameFile(
+  path: string,
+  newName: string,
+  updateLinks?: boolean
+): Promise<MoveResult> {
+  return invoke<MoveResult>("rename_file", {
+    path,
+    new_name: newName,
+    update_links: updateLinks,
+  });
 }
 
 export async function parseFile(
@@ -96,24 +448,122 @@ export async function parseFile(
   return invoke<{ nodes: TreeNode[] }>("parse_file", { path, content });
 }
 
+export async function assignBlockId(
+  path: string,
+  nodeId: string
+): Promise<string> {
+  return invoke<string>("assign_block_id", { path, node_id: nodeId });
+}
+
+export async function toggleTask(
+  path: string,
+  nodeId: string
+): Promise<TaskState> {
+  return invoke<TaskState>("toggle_task", { path, node_id: nodeId });
+}
+
 export async function queryByTag(
   tagNames: string[],
   scopeNodeId?: string,
-  paths?: string[]
+  paths?: string[],
+  task?: TaskState,
+  inherited?: boolean
 ): Promise<QueryResultItem[]> {
   return invoke<QueryResultItem[]>("query_by_tag", {
     tag_names: tagNames,
     scope_node_id: scopeNodeId,
     paths,
+    task,
+    inherited,
+  });
+}
+
+/**
+ * Run a boolean query, e.g. `#decision AND NOT #superseded`,
+ * `(#ci OR #infra) AND path:projects/*`, `"rollback plan" task:open`, or
+ * `#decision status = accepted due < 2026-12-01`.
+ * Invalid syntax rejects with a message that includes the position.
+ */
+export async function queryNodes(
+  query: string,
+  paths?: string[],
+  inherited?: boolean,
+  sortBy?: string,
+  descending?: boolean
+): Promise<QueryResultItem[]> {
+  return invoke<QueryResultItem[]>("query_nodes", {
+    query,
+    paths,
+    inherited,
+    sort_by: sortBy,
+    descending,
   });
 }
 
+/**
+ * List items whose wikilinks point at the note at `path`, or with `nodeId` at
+ * that item's `^block-id`, each with its parent_path.
+ */
+export async function getBacklinks(
+  path: string,
+  nodeId?: string
+): Promise<QueryResultItem[]> {
+  return invoke<QueryResultItem[]>("get_backlinks", { path, node_id: nodeId });
+}
+
 export async function searchFullText(
   query: string,
   paths?: string[],
-  fuzzy?: boolean
+  fuzzy?: boolean,
+  options?: SearchOptions
 ): Promise<SearchMatch[]> {
-  return invoke<SearchMatch[]>("search_full_text", { query, paths, fuzzy });
+  return invoke<SearchMatch[]>("search_full_text", {
+    query,
+    paths,
+    fuzzy,
+    options,
+  });
+}
+
+/**
+ * Without `selection`, returns a preview and writes nothing. With
+ * `selection`, applies the chosen changes.
+ */
+export async function replaceInVault(
+  query: string,
+  replacement: string,
+  options?: SearchOptions,
+  paths?: string[],
+  selection?: ReplaceSelection[]
+): Promise<FileReplace[]> {
+  return invoke<FileReplace[]>("replace_in_vault", {
+    query,
+    replacement,
+    options,
+    paths,
+    selection,
+  });
+}
+
+export async function listTags(): Promise<TagInfo[]> {
+  return invoke<TagInfo[]>("list_tags");
+}
+
+/**
+ * Rename (or merge) a tag across the vault. Use dryRun to preview counts.
+ */
+export async function renameTag(
+  from: string,
+  to: string,
+  includeChildren?: boolean,
+  dryRun?: boolean
+): Promise<TagRenameReport> {
+  return invoke<TagRenameReport>("rename_tag", {
+    from,
+    to,
+    include_children: includeChildren,
+    dry_run: dryRun,
+  });
 }
 
 export async function logFromFrontend(